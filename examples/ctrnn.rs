@@ -2,7 +2,7 @@
 // try to reproduce results from: http://www.tinyblueplanet.com/easy/FCSReport.pdf
 extern crate rustneat;
 
-use rustneat::{Ctrnn, CtrnnNeuralNetwork};
+use rustneat::{Activation, Ctrnn, CtrnnNeuralNetwork};
 
 #[cfg(feature = "telemetry")]
 mod telemetry_helper;
@@ -49,11 +49,12 @@ fn activate_minimal_ctrnn_node(initial: f64) {
         5.0,
         0.01,
         &CtrnnNeuralNetwork {
-            y: &vec![initial],
-            tau: &vec![1.0],
-            wji: &vec![0.0],
-            theta: &vec![0.0],
-            i: &vec![0f64],
+            y: &[initial],
+            tau: &[1.0],
+            wji: &[0.0],
+            theta: &[0.0],
+            i: &[0f64],
+            activations: &[Activation::Sigmoid],
         },
     );
 }
@@ -86,11 +87,12 @@ fn activate_step_size_test(step_size: f64) {
         15.0 * step_size,
         step_size,
         &CtrnnNeuralNetwork {
-            y: &vec![1.0],
-            tau: &vec![1.0],
-            wji: &vec![0.0],
-            theta: &vec![0.0],
-            i: &vec![0.5],
+            y: &[1.0],
+            tau: &[1.0],
+            wji: &[0.0],
+            theta: &[0.0],
+            i: &[0.5],
+            activations: &[Activation::Sigmoid],
         },
     );
 }
@@ -2,15 +2,11 @@ extern crate ctrlc;
 extern crate pyo3;
 extern crate rand;
 extern crate rustneat;
-extern crate serde;
-extern crate serde_json;
 
 use pyo3::prelude::*;
 use pyo3::types::{IntoPyDict, PyModule};
 use pyo3::PyResult;
-use rustneat::{Environment, Gene, Genome, MutationConfig, Organism, Population};
-use serde::{Deserialize, Serialize};
-use std::fs;
+use rustneat::{Champion, Environment, Gene, Genome, MutationConfig, Organism, Population};
 use std::path::Path;
 use std::process;
 
@@ -47,97 +43,61 @@ fn compute_novelty(behavior: &[f64], all_behaviors: &[&Vec<f64>], k: usize) -> f
 
 const CONFIG_FILE: &str = "best_config.json";
 const CHAMPION_FILE: &str = "champion.json";
+const CHECKPOINT_FILE: &str = "checkpoint.json";
+const CHECKPOINT_GENERATIONS: usize = 25;
 
-/// Serializable champion genome
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ChampionFile {
-    fitness: f64,
-    generation: usize,
-    neurons_len: usize,
-    genes: Vec<(usize, usize, f64, bool, bool)>, // (in, out, weight, enabled, bias)
+/// Neurons with a bias, as (neuron id, bias)
+fn node_biases(genome: &Genome) -> Vec<(usize, f64)> {
+    genome
+        .get_nodes()
+        .iter()
+        .filter(|node| node.bias() != 0.0)
+        .map(|node| (node.id(), node.bias()))
+        .collect()
 }
 
-impl ChampionFile {
-    fn save(&self, path: &str) {
-        let content = serde_json::to_string_pretty(self).unwrap();
-        fs::write(path, content).expect("Failed to save champion");
-        println!("Champion saved to {}", path);
-    }
-
-    fn from_organism(organism: &Organism, fitness: f64, generation: usize) -> Self {
-        let genes = organism
-            .genome
-            .get_genes()
-            .iter()
-            .map(|g| {
-                (
-                    g.in_neuron_id(),
-                    g.out_neuron_id(),
-                    g.weight(),
-                    g.enabled(),
-                    g.is_bias(),
-                )
-            })
-            .collect();
-        ChampionFile {
-            fitness,
-            generation,
-            neurons_len: organism.genome.len(),
-            genes,
+fn apply_biases(genome: &mut Genome, biases: &[(usize, f64)]) {
+    for (neuron_id, bias) in biases {
+        if let Some(node) = genome.get_node(*neuron_id) {
+            let mut node = *node;
+            node.set_bias(*bias);
+            genome.add_node(node);
         }
     }
 }
 
-/// Serializable configuration file
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ConfigFile {
-    weight_mutation_rate: f64,
-    add_connection_rate: f64,
-    add_neuron_rate: f64,
-    toggle_expression_rate: f64,
-    weight_perturbation_rate: f64,
-    toggle_bias_rate: f64,
-    compatibility_threshold: f64,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    best_fitness: Option<f64>,
-    #[serde(default = "default_weight_init_range")]
-    weight_init_range: f64,
-    #[serde(default = "default_weight_mutate_power")]
-    weight_mutate_power: f64,
-}
-
-fn default_weight_init_range() -> f64 {
-    3.0
-}
-fn default_weight_mutate_power() -> f64 {
-    1.5
+/// Time constant of every neuron, as (neuron id, tau)
+fn node_taus(genome: &Genome) -> Vec<(usize, f64)> {
+    genome
+        .get_nodes()
+        .iter()
+        .map(|node| (node.id(), node.tau()))
+        .collect()
 }
 
-impl ConfigFile {
-    fn load(path: &str) -> Option<Self> {
-        if Path::new(path).exists() {
-            let content = fs::read_to_string(path).ok()?;
-            serde_json::from_str(&content).ok()
-        } else {
-            None
+fn apply_taus(genome: &mut Genome, taus: &[(usize, f64)]) {
+    for (neuron_id, tau) in taus {
+        if let Some(node) = genome.get_node(*neuron_id) {
+            let mut node = *node;
+            node.set_tau(*tau);
+            genome.add_node(node);
         }
     }
+}
 
-    fn to_mutation_config(&self) -> MutationConfig {
-        MutationConfig::new()
-            .weight_mutation_rate(self.weight_mutation_rate)
-            .add_connection_rate(self.add_connection_rate)
-            .add_neuron_rate(self.add_neuron_rate)
-            .toggle_expression_rate(self.toggle_expression_rate)
-            .weight_perturbation_rate(self.weight_perturbation_rate)
-            .toggle_bias_rate(self.toggle_bias_rate)
-            .compatibility_threshold(self.compatibility_threshold)
-            .weight_init_range(self.weight_init_range)
-            .weight_mutate_power(self.weight_mutate_power)
-            .tau(0.1)
-            .step_time(0.5)
-            .build()
-    }
+/// Settings used when there is no config file, and for the settings it lacks
+fn default_config() -> MutationConfig {
+    let mut config = MutationConfig::default();
+    config.add_connection_rate = 0.20;
+    config.add_neuron_rate = 0.10;
+    config.weight_mutation_rate = 0.80;
+    config.toggle_expression_rate = 0.005;
+    config.compatibility_threshold = 1.5;
+    config.weight_init_range = 3.0;
+    config.weight_mutate_power = 1.5;
+    config.tau = 0.1;
+    config.step_time = 0.5;
+    config
 }
 
 #[cfg(feature = "telemetry")]
@@ -167,20 +127,23 @@ impl PyOrganism {
 
 #[pyfunction]
 fn create_organism(
-    genes: Vec<(usize, usize, f64, bool, bool)>,
+    genes: Vec<(usize, usize, f64, bool)>,
     neurons_len: usize,
+    biases: Vec<(usize, f64)>,
+    taus: Vec<(usize, f64)>,
 ) -> PyResult<PyOrganism> {
     // Reconstruct genes from serialized data
     let gene_vec: Vec<Gene> = genes
         .into_iter()
-        .map(|(in_id, out_id, weight, enabled, is_bias)| {
-            Gene::new(in_id, out_id, weight, enabled, is_bias)
-        })
+        .map(|(in_id, out_id, weight, enabled)| Gene::new(in_id, out_id, weight, enabled))
         .collect();
 
     // Reconstruct genome with proper neuron count (neurons_len = last_neuron_id + 1)
     let last_neuron_id = if neurons_len > 0 { neurons_len - 1 } else { 0 };
-    let genome = Genome::from_genes(gene_vec, last_neuron_id);
+    let mut genome = Genome::try_from_genes(gene_vec, last_neuron_id)
+        .map_err(|error| pyo3::exceptions::PyValueError::new_err(error.to_string()))?;
+    apply_biases(&mut genome, &biases);
+    apply_taus(&mut genome, &taus);
     let organism = Organism::new(genome);
 
     Ok(PyOrganism { organism })
@@ -291,7 +254,6 @@ impl LunarLanderMultiprocess {
                         gene.out_neuron_id(),
                         gene.weight(),
                         gene.enabled(),
-                        gene.is_bias(),
                     )
                 })
                 .collect::<Vec<_>>();
@@ -300,6 +262,9 @@ impl LunarLanderMultiprocess {
             let dict = builtins.call_method0("dict").unwrap();
             dict.set_item("genes", genes_list).unwrap();
             dict.set_item("neurons_len", neurons_len).unwrap();
+            dict.set_item("biases", node_biases(&organism.genome))
+                .unwrap();
+            dict.set_item("taus", node_taus(&organism.genome)).unwrap();
 
             let worker_fn = self
                 .worker_module
@@ -359,6 +324,8 @@ impl Environment for LunarLanderMultiprocess {
             for &idx in &to_evaluate {
                 let organism = &organisms[idx];
                 let neurons_len = organism.genome.len();
+                let biases = node_biases(&organism.genome);
+                let taus = node_taus(&organism.genome);
                 let genes_list = organism
                     .genome
                     .get_genes()
@@ -369,7 +336,6 @@ impl Environment for LunarLanderMultiprocess {
                             gene.out_neuron_id(),
                             gene.weight(),
                             gene.enabled(),
-                            gene.is_bias(),
                         )
                     })
                     .collect::<Vec<_>>();
@@ -379,6 +345,8 @@ impl Environment for LunarLanderMultiprocess {
                     let dict = builtins.call_method0("dict").unwrap();
                     dict.set_item("genes", &genes_list).unwrap();
                     dict.set_item("neurons_len", neurons_len).unwrap();
+                    dict.set_item("biases", &biases).unwrap();
+                    dict.set_item("taus", &taus).unwrap();
                     batch_data.push((dict, false));
                 }
             }
@@ -484,43 +452,49 @@ fn main() {
     #[cfg(feature = "telemetry")]
     telemetry_helper::enable_telemetry(format!("?max_fitness={}", max_fitness).as_str(), true);
 
-    // Load config from file or use defaults
-    let mut population = if let Some(config_file) = ConfigFile::load(CONFIG_FILE) {
+    // Resume from the last checkpoint, or load config from file or use defaults
+    let mut population = if Path::new(CHECKPOINT_FILE).exists() {
+        match Population::load_checkpoint(CHECKPOINT_FILE) {
+            Ok(population) => {
+                println!(
+                    "Resuming from {} at generation {}\n",
+                    CHECKPOINT_FILE,
+                    population.generation()
+                );
+                population
+            }
+            Err(error) => {
+                println!("Could not load {}: {}", CHECKPOINT_FILE, error);
+                process::exit(1);
+            }
+        }
+    } else if Path::new(CONFIG_FILE).exists() {
+        let (config, report) =
+            match MutationConfig::load_with_defaults(CONFIG_FILE, &default_config()) {
+                Ok(loaded) => loaded,
+                Err(error) => {
+                    println!("Could not load {}: {}", CONFIG_FILE, error);
+                    process::exit(1);
+                }
+            };
         println!("Loaded config from {}", CONFIG_FILE);
-        println!(
-            "  add_connection_rate: {:.4}",
-            config_file.add_connection_rate
-        );
-        println!("  add_neuron_rate: {:.4}", config_file.add_neuron_rate);
-        println!(
-            "  weight_mutation_rate: {:.4}",
-            config_file.weight_mutation_rate
-        );
-        if let Some(fitness) = config_file.best_fitness {
-            println!("  (tuned with fitness: {:.2})", fitness);
+        println!("  add_connection_rate: {:.4}", config.add_connection_rate);
+        println!("  add_neuron_rate: {:.4}", config.add_neuron_rate);
+        println!("  weight_mutation_rate: {:.4}", config.weight_mutation_rate);
+        println!("  {} settings kept their default", report.defaulted.len());
+        if !report.unknown.is_empty() {
+            println!("  ignored: {}", report.unknown.join(", "));
         }
         println!();
-        let config = config_file.to_mutation_config();
-        println!();
         Population::create_population_initialized_with_config(150, 8, 2, config)
     } else {
         println!("No {} found, using default config\n", CONFIG_FILE);
-        let mut config = MutationConfig::default();
-        config.add_connection_rate = 0.20;
-        config.add_neuron_rate = 0.10;
-        config.weight_mutation_rate = 0.80;
-        config.toggle_expression_rate = 0.005;
-        config.compatibility_threshold = 1.5;
-        config.weight_init_range = 3.0;
-        config.weight_mutate_power = 1.5;
-        config.tau = 0.1;
-        config.step_time = 0.5;
-        Population::create_population_initialized_with_config(150, 8, 2, config)
+        Population::create_population_initialized_with_config(150, 8, 2, default_config())
     };
 
     let environment = LunarLanderMultiprocess::new();
     let mut champion: Option<Organism> = None;
-    let mut generations = 0;
+    let mut generations = population.generation();
     let mut best_fitness = 300.0; // Typical starting fitness (-200 + 500 offset)
     let mut last_verified_fitness = 0.0; // Track last fitness we verified to avoid re-verifying same champion
     let improvement_threshold = 20.0; // Show render when fitness improves by at least 20 points
@@ -536,20 +510,27 @@ fn main() {
     use std::time::Instant;
     let start_time = Instant::now();
     let mut last_stats_time = Instant::now();
-    let mut last_stats_gen = 0;
+    let mut last_stats_gen = generations;
+    let first_generation = generations;
 
     while champion.is_none() {
         population.evolve();
         population.evaluate_in(&environment);
         generations += 1;
 
+        if generations % CHECKPOINT_GENERATIONS == 0 {
+            if let Err(error) = population.save_checkpoint(CHECKPOINT_FILE) {
+                println!("Could not save {}: {}", CHECKPOINT_FILE, error);
+            }
+        }
+
         // Show performance stats every 50 generations
         if generations % 50 == 0 {
             let elapsed = last_stats_time.elapsed().as_secs_f64();
             let gens_since_last = generations - last_stats_gen;
             let gen_per_sec = gens_since_last as f64 / elapsed;
             let total_elapsed = start_time.elapsed().as_secs_f64();
-            let avg_gen_per_sec = generations as f64 / total_elapsed;
+            let avg_gen_per_sec = (generations - first_generation) as f64 / total_elapsed;
 
             println!(
                 "\n[Performance] Gen {}: {:.1} gen/s (avg: {:.1} gen/s, total time: {:.1}min)",
@@ -619,12 +600,15 @@ fn main() {
                         best_fitness = average_fitness;
 
                         // Save champion genome
-                        let champion_data = ChampionFile::from_organism(
-                            &tmp_champion,
-                            current_fitness,
-                            generations,
-                        );
-                        champion_data.save(CHAMPION_FILE);
+                        let mut champion_data = Champion::new(&tmp_champion);
+                        champion_data.generation = generations;
+                        champion_data
+                            .metadata
+                            .insert("environment".to_string(), "LunarLander-v3".to_string());
+                        match champion_data.save(CHAMPION_FILE) {
+                            Ok(()) => println!("Champion saved to {}", CHAMPION_FILE),
+                            Err(error) => println!("Could not save {}: {}", CHAMPION_FILE, error),
+                        }
                     } else {
                         println!("✗ Not consistent enough. Continuing evolution...\n");
                     }
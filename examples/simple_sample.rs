@@ -2,6 +2,7 @@ extern crate rand;
 extern crate rustneat;
 
 use rustneat::Environment;
+use rustneat::MutationConfig;
 use rustneat::Organism;
 use rustneat::Population;
 
@@ -34,7 +35,9 @@ fn main() {
     #[cfg(feature = "telemetry")]
     std::thread::sleep(std::time::Duration::from_millis(2000));
 
-    let mut population = Population::create_population(150);
+    // XOR is a static task, a single feed-forward pass gives exact outputs
+    let config = MutationConfig::new().feed_forward(true).build();
+    let mut population = Population::create_population_initialized_with_config(150, 2, 1, config);
     let environment = XORClassification;
     let mut champion: Option<Organism> = None;
     while champion.is_none() {
@@ -1,49 +1,12 @@
 extern crate pyo3;
 extern crate rustneat;
-extern crate serde;
-extern crate serde_json;
 
 use pyo3::prelude::*;
 use pyo3::types::IntoPyDict;
-use rustneat::{Gene, Genome, Organism};
-use serde::Deserialize;
-use std::fs;
+use rustneat::{Champion, Organism};
 
 const CHAMPION_FILE: &str = "champion.json";
 
-#[derive(Debug, Deserialize)]
-struct ChampionFile {
-    fitness: f64,
-    generation: usize,
-    neurons_len: usize,
-    genes: Vec<(usize, usize, f64, bool, bool)>,
-}
-
-impl ChampionFile {
-    fn load(path: &str) -> Option<Self> {
-        let content = fs::read_to_string(path).ok()?;
-        serde_json::from_str(&content).ok()
-    }
-
-    fn to_organism(&self) -> Organism {
-        let gene_vec: Vec<Gene> = self
-            .genes
-            .iter()
-            .map(|(i, o, w, e, b)| Gene::new(*i, *o, *w, *e, *b))
-            .collect();
-        let last_neuron_id = if self.neurons_len > 0 {
-            self.neurons_len - 1
-        } else {
-            0
-        };
-        let genome = Genome::from_genes(gene_vec, last_neuron_id);
-        let mut org = Organism::new(genome);
-        org.tau = 0.1;
-        org.step_time = 0.5;
-        org
-    }
-}
-
 fn run_episode(
     organism: &mut Organism,
     py: Python,
@@ -114,13 +77,16 @@ fn main() {
 
     println!("Loading champion from {}...", CHAMPION_FILE);
 
-    let champion_data = ChampionFile::load(CHAMPION_FILE).expect("Failed to load champion.json");
+    let champion_data = Champion::load(CHAMPION_FILE).expect("Failed to load champion.json");
 
     println!("Champion info:");
     println!("  Fitness: {:.2}", champion_data.fitness);
     println!("  Generation: {}", champion_data.generation);
-    println!("  Neurons: {}", champion_data.neurons_len);
-    println!("  Genes: {}", champion_data.genes.len());
+    println!("  Neurons: {}", champion_data.genome.len());
+    println!("  Genes: {}", champion_data.genome.total_genes());
+    for (key, value) in &champion_data.metadata {
+        println!("  {}: {}", key, value);
+    }
     println!();
 
     let mut organism = champion_data.to_organism();
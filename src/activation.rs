@@ -0,0 +1,74 @@
+use crate::ctrnn::Ctrnn;
+
+/// Activation function applied to the state of a neuron
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Activation {
+    /// 1 / (1 + e^-x)
+    #[default]
+    Sigmoid,
+    /// Hyperbolic tangent, in (-1, 1)
+    Tanh,
+    /// max(0, x)
+    Relu,
+    /// e^(-x²), peaks at 0
+    Gaussian,
+    /// sin(x)
+    Sine,
+    /// 1 if x > 0, else 0
+    Step,
+    /// x
+    Identity,
+    /// |x|
+    Abs,
+}
+
+impl Activation {
+    /// Every available activation function
+    pub const ALL: [Activation; 8] = [
+        Activation::Sigmoid,
+        Activation::Tanh,
+        Activation::Relu,
+        Activation::Gaussian,
+        Activation::Sine,
+        Activation::Step,
+        Activation::Identity,
+        Activation::Abs,
+    ];
+
+    /// Apply the function to a value
+    pub fn activate(&self, x: f64) -> f64 {
+        match self {
+            Activation::Sigmoid => Ctrnn::sigmoid(x),
+            Activation::Tanh => x.tanh(),
+            Activation::Relu => x.max(0f64),
+            Activation::Gaussian => (-x * x).exp(),
+            Activation::Sine => x.sin(),
+            Activation::Step => {
+                if x > 0f64 {
+                    1f64
+                } else {
+                    0f64
+                }
+            }
+            Activation::Identity => x,
+            Activation::Abs => x.abs(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn activation_functions_should_return_expected_values() {
+        assert!((Activation::Sigmoid.activate(0f64) - 0.5f64).abs() < f64::EPSILON);
+        assert!(Activation::Tanh.activate(0f64).abs() < f64::EPSILON);
+        assert!(Activation::Relu.activate(-2f64).abs() < f64::EPSILON);
+        assert!((Activation::Gaussian.activate(0f64) - 1f64).abs() < f64::EPSILON);
+        assert!((Activation::Step.activate(0.1f64) - 1f64).abs() < f64::EPSILON);
+        assert!((Activation::Identity.activate(-2f64) + 2f64).abs() < f64::EPSILON);
+        assert!((Activation::Abs.activate(-2f64) - 2f64).abs() < f64::EPSILON);
+    }
+}
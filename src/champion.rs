@@ -0,0 +1,294 @@
+use crate::gene::Gene;
+use crate::genome::Genome;
+use crate::mutation_config::RecurrencePolicy;
+use crate::organism::Organism;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::Path;
+
+/// Version written by `Champion::save`. Version 1 is the format of the champion
+/// files saved by the examples, with genes as tuples and no CTRNN settings.
+pub const CHAMPION_FORMAT_VERSION: u32 = 2;
+
+/// CTRNN settings of the organisms that saved version 1 files
+const LEGACY_TAU: f64 = 0.1;
+const LEGACY_STEP_TIME: f64 = 0.5;
+
+/// An organism saved to disk with what is needed to run it again and to know
+/// where it came from.
+///
+/// Files of older versions are migrated on load and unknown fields are ignored,
+/// so files written by a newer minor revision of the format still load.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Champion {
+    /// Network of the organism
+    pub genome: Genome,
+    /// `Organism::step_time`
+    pub step_time: f64,
+    /// `Organism::feed_forward`
+    #[serde(default)]
+    pub feed_forward: bool,
+    /// Loops the genome may have, checked on load (default `Recurrent`)
+    #[serde(default)]
+    pub recurrence_policy: RecurrencePolicy,
+    /// Fitness of the organism when it was saved
+    pub fitness: f64,
+    /// Generation the organism was found in
+    #[serde(default)]
+    pub generation: usize,
+    /// Number of sensors the network reads
+    pub input_neurons: usize,
+    /// Number of outputs the network writes
+    pub output_neurons: usize,
+    /// Free-form information such as the environment or the config used
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+#[derive(serde::Serialize)]
+struct VersionedChampion<'a> {
+    version: u32,
+    #[serde(flatten)]
+    champion: &'a Champion,
+}
+
+/// Champion file written by the examples before the format was versioned
+#[derive(serde::Deserialize)]
+struct ChampionV1 {
+    fitness: f64,
+    generation: usize,
+    neurons_len: usize,
+    // (in, out, weight, enabled, input neuron has a bias of one)
+    genes: Vec<(usize, usize, f64, bool, bool)>,
+    #[serde(default)]
+    biases: Vec<(usize, f64)>,
+    #[serde(default)]
+    taus: Vec<(usize, f64)>,
+}
+
+impl ChampionV1 {
+    fn migrate(self) -> io::Result<Champion> {
+        let genes = self
+            .genes
+            .iter()
+            .map(|&(in_neuron_id, out_neuron_id, weight, enabled, _)| {
+                Gene::new(in_neuron_id, out_neuron_id, weight, enabled)
+            })
+            .collect();
+        let mut genome = Genome::try_from_genes(genes, self.neurons_len.saturating_sub(1))
+            .map_err(invalid_data)?;
+        genome.set_tau(LEGACY_TAU);
+        let bias_flags = self
+            .genes
+            .iter()
+            .filter(|gene| gene.4)
+            .map(|gene| (gene.0, None));
+        let biases = self
+            .biases
+            .iter()
+            .map(|&(neuron_id, bias)| (neuron_id, Some(bias)));
+        for (neuron_id, bias) in bias_flags.chain(biases) {
+            if let Some(node) = genome.get_node(neuron_id) {
+                let mut node = *node;
+                node.set_bias(bias.unwrap_or(node.bias() + 1f64));
+                genome.add_node(node);
+            }
+        }
+        for &(neuron_id, tau) in &self.taus {
+            if let Some(node) = genome.get_node(neuron_id) {
+                let mut node = *node;
+                node.set_tau(tau);
+                genome.add_node(node);
+            }
+        }
+        genome.validate().map_err(invalid_data)?;
+        Ok(Champion {
+            genome,
+            step_time: LEGACY_STEP_TIME,
+            feed_forward: false,
+            recurrence_policy: RecurrencePolicy::Recurrent,
+            fitness: self.fitness,
+            generation: self.generation,
+            input_neurons: 0,
+            output_neurons: 0,
+            metadata: BTreeMap::new(),
+        })
+    }
+}
+
+fn invalid_data<E: ToString>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+impl Champion {
+    /// Champion holding a copy of the organism and its CTRNN settings
+    pub fn new(organism: &Organism) -> Champion {
+        Champion {
+            genome: organism.genome.clone(),
+            step_time: organism.step_time,
+            feed_forward: organism.feed_forward,
+            recurrence_policy: RecurrencePolicy::Recurrent,
+            fitness: organism.fitness,
+            generation: 0,
+            input_neurons: organism.genome.input_neurons(),
+            output_neurons: organism.genome.output_neurons(),
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Organism with the saved genome, CTRNN settings and fitness
+    pub fn to_organism(&self) -> Organism {
+        let mut organism = Organism::new(self.genome.clone());
+        organism.step_time = self.step_time;
+        organism.feed_forward = self.feed_forward;
+        organism.fitness = self.fitness;
+        organism
+    }
+
+    /// Write the champion as JSON in the current format version
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(&VersionedChampion {
+            version: CHAMPION_FORMAT_VERSION,
+            champion: self,
+        })?;
+        fs::write(path, content)
+    }
+
+    /// Read a champion file of any known version. Files without a version are
+    /// version 1. Genomes breaking the saved recurrence policy are rejected with
+    /// `io::ErrorKind::InvalidData`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Champion> {
+        let value: Value = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        Champion::from_value(value)
+    }
+
+    fn from_value(value: Value) -> io::Result<Champion> {
+        let version = match value.get("version") {
+            None => 1,
+            Some(version) => version
+                .as_u64()
+                .ok_or_else(|| invalid_data("champion version is not a number"))?,
+        };
+        let champion = match version {
+            1 => serde_json::from_value::<ChampionV1>(value)?.migrate()?,
+            2 => serde_json::from_value::<Champion>(value)?,
+            _ => {
+                return Err(invalid_data(format!(
+                    "champion format version {} is newer than the supported version {}",
+                    version, CHAMPION_FORMAT_VERSION
+                )))
+            }
+        };
+        champion.check_io_counts()?;
+        champion
+            .genome
+            .validate_recurrence_policy(champion.recurrence_policy)
+            .map_err(invalid_data)?;
+        Ok(champion)
+    }
+
+    fn check_io_counts(&self) -> io::Result<()> {
+        if self.genome.has_io_roles()
+            && (self.genome.input_neurons() != self.input_neurons
+                || self.genome.output_neurons() != self.output_neurons)
+        {
+            return Err(invalid_data(format!(
+                "champion has {} inputs and {} outputs but its genome {} and {}",
+                self.input_neurons,
+                self.output_neurons,
+                self.genome.input_neurons(),
+                self.genome.output_neurons()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Organism {
+    /// Save the organism as a `Champion` file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        Champion::new(self).save(path)
+    }
+
+    /// Load an organism from a `Champion` file of any known version
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Organism> {
+        Champion::load(path).map(|champion| champion.to_organism())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn champion_should_round_trip() {
+        let mut organism = Organism::new(Genome::new_initialized(2, 1));
+        organism.step_time = 0.3;
+        organism.fitness = 4f64;
+        let mut champion = Champion::new(&organism);
+        champion.generation = 12;
+        champion
+            .metadata
+            .insert("environment".to_string(), "xor".to_string());
+        let path = std::env::temp_dir().join(format!("rustneat-champion-{}", std::process::id()));
+        champion.save(&path).unwrap();
+        let loaded = Champion::load(&path).unwrap();
+        let loaded_organism = Organism::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(loaded.genome.diff(&organism.genome).is_empty());
+        assert_eq!(loaded.generation, 12);
+        assert_eq!(loaded.input_neurons, 2);
+        assert_eq!(loaded.metadata["environment"], "xor");
+        assert!((loaded_organism.step_time - 0.3).abs() < f64::EPSILON);
+        assert!((loaded_organism.fitness - 4f64).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn version_1_files_should_be_migrated() {
+        let champion = Champion::from_value(json!({
+            "fitness": 600.0,
+            "generation": 42,
+            "neurons_len": 3,
+            "genes": [[0, 2, 0.5, true, true], [1, 2, -0.5, true, false]],
+            "biases": [[2, 0.25]],
+            "taus": [[2, 0.05]]
+        }))
+        .unwrap();
+        assert_eq!(champion.generation, 42);
+        assert!((champion.step_time - LEGACY_STEP_TIME).abs() < f64::EPSILON);
+        let node = |id| *champion.genome.get_node(id).unwrap();
+        assert!((node(0).bias() - 1f64).abs() < f64::EPSILON);
+        assert!((node(0).tau() - LEGACY_TAU).abs() < f64::EPSILON);
+        assert!((node(2).bias() - 0.25).abs() < f64::EPSILON);
+        assert!((node(2).tau() - 0.05).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn genomes_breaking_the_recurrence_policy_should_be_rejected() {
+        let mut genome = Genome::new_initialized(1, 1);
+        genome.add_gene(Gene::new(1, 1, 0.5, true));
+        let mut champion = Champion::new(&Organism::new(genome));
+        let value = |champion: &Champion| {
+            serde_json::to_value(VersionedChampion {
+                version: CHAMPION_FORMAT_VERSION,
+                champion,
+            })
+            .unwrap()
+        };
+        assert!(Champion::from_value(value(&champion)).is_ok());
+        champion.recurrence_policy = RecurrencePolicy::NoSelfLoops;
+        let error = Champion::from_value(value(&champion)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn newer_versions_should_be_rejected() {
+        let error =
+            Champion::from_value(json!({ "version": CHAMPION_FORMAT_VERSION + 1 })).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
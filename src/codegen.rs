@@ -0,0 +1,996 @@
+use crate::activation::Activation;
+use crate::organism::{CtrnnLayout, Organism};
+use std::cmp;
+use std::env;
+use std::error::Error;
+use std::fmt::{self, Write};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const STEP_SIZE: f64 = 0.01;
+
+static CHECK_DIRECTORY_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Language of the code written by `Codegen`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetLanguage {
+    /// A Rust module without dependencies
+    Rust,
+    /// A C99 header and source pair, linked with the math library
+    C,
+    /// A Python module using only the standard library
+    Python,
+}
+
+/// Error of `Codegen::check`
+#[derive(Debug)]
+pub enum CodegenError {
+    /// The generated code could not be written or run
+    Io(io::Error),
+    /// The compiler or the interpreter failed
+    Tool {
+        /// Command that failed
+        command: String,
+        /// What the command wrote to stderr
+        stderr: String,
+    },
+    /// The generated code returned something else than the organism
+    Mismatch {
+        /// Position of the sample in the checked sequence
+        sample: usize,
+        /// Position of the output
+        output: usize,
+        /// Output of `Organism::activate`
+        expected: f64,
+        /// Output of the generated code, None if it was missing
+        found: Option<f64>,
+    },
+}
+
+impl fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CodegenError::Io(error) => write!(f, "Cannot run the generated code: {}", error),
+            CodegenError::Tool { command, stderr } => {
+                write!(f, "`{}` failed: {}", command, stderr.trim_end())
+            }
+            CodegenError::Mismatch {
+                sample,
+                output,
+                expected,
+                found: Some(found),
+            } => write!(
+                f,
+                "Output {} of sample {} is {} but the organism returns {}",
+                output, sample, found, expected
+            ),
+            CodegenError::Mismatch {
+                sample,
+                output,
+                expected,
+                found: None,
+            } => write!(
+                f,
+                "Output {} of sample {} is missing, the organism returns {}",
+                output, sample, expected
+            ),
+        }
+    }
+}
+
+impl Error for CodegenError {}
+
+impl From<io::Error> for CodegenError {
+    fn from(error: io::Error) -> CodegenError {
+        CodegenError::Io(error)
+    }
+}
+
+/// Writes the network of an organism as standalone source code, to run a
+/// champion without this crate.
+///
+/// The generated code integrates the CTRNN with the same Euler steps as
+/// `Organism::activate`, keeping the state of the neurons between activations,
+/// or makes the single topological pass of `Organism::feed_forward` when it is
+/// set and the genome is acyclic. Weights, biases, time constants and activation
+/// functions are inlined as constants.
+#[derive(Debug, Clone)]
+pub struct Codegen {
+    organism: Organism,
+    inputs: usize,
+    outputs: usize,
+    output_indexes: Vec<usize>,
+    bias: Vec<f64>,
+    tau: Vec<f64>,
+    activations: Vec<usize>,
+    // (to, from, weight), in evaluation order
+    connections: Vec<(usize, usize, f64)>,
+    feed_forward: bool,
+    steps: usize,
+}
+
+impl Codegen {
+    /// Code generator for an organism whose genome has input and output roles
+    pub fn new(organism: &Organism) -> Codegen {
+        Codegen::with_io(
+            organism,
+            organism.genome.input_neurons(),
+            organism.genome.output_neurons(),
+        )
+    }
+
+    /// Code generator reading `inputs` sensors and writing `outputs` outputs,
+    /// needed for genomes without roles whose layout depends on those counts
+    pub fn with_io(organism: &Organism, inputs: usize, outputs: usize) -> Codegen {
+        let CtrnnLayout {
+            indexes,
+            sensors_len,
+            mut output_indexes,
+        } = organism.ctrnn_layout(inputs, outputs);
+        let neurons_len = indexes.len();
+        output_indexes.truncate(outputs);
+
+        let order = if organism.feed_forward {
+            organism.genome.topological_order()
+        } else {
+            None
+        };
+        let connections = match order {
+            Some(ref order) => {
+                order
+                    .iter()
+                    .flat_map(|neuron_id| {
+                        organism.genome.get_genes().iter().filter(move |gene| {
+                            gene.enabled() && gene.out_neuron_id() == *neuron_id
+                        })
+                    })
+                    .map(|gene| {
+                        (
+                            indexes[&gene.out_neuron_id()],
+                            indexes[&gene.in_neuron_id()],
+                            gene.weight(),
+                        )
+                    })
+                    .collect()
+            }
+            None => organism
+                .get_weights(&indexes)
+                .iter()
+                .enumerate()
+                .filter(|(_, weight)| **weight != 0f64)
+                .map(|(position, weight)| (position / neurons_len, position % neurons_len, *weight))
+                .collect(),
+        };
+
+        let mut reset_organism = organism.clone();
+        reset_organism.reset_state();
+        Codegen {
+            organism: reset_organism,
+            inputs: cmp::min(sensors_len, neurons_len),
+            outputs: output_indexes.len(),
+            output_indexes,
+            bias: organism.get_bias(&indexes),
+            tau: organism.get_taus(&indexes),
+            activations: organism
+                .get_activations(&indexes)
+                .iter()
+                .map(|activation| activation_kind(*activation))
+                .collect(),
+            connections,
+            feed_forward: order.is_some(),
+            steps: (organism.step_time / STEP_SIZE) as usize,
+        }
+    }
+
+    /// Number of sensors read by the generated code
+    pub fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    /// Number of outputs written by the generated code
+    pub fn outputs(&self) -> usize {
+        self.outputs
+    }
+
+    /// Rust module with a `Network` struct, whose `activate(&mut self, inputs,
+    /// outputs)` updates the state like `Organism::activate`
+    pub fn to_rust(&self) -> String {
+        let mut code = String::new();
+        writeln!(
+            code,
+            "//! Network generated by rustneat from an evolved organism."
+        )
+        .unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "/// Number of sensors read by `Network::activate`").unwrap();
+        writeln!(code, "pub const INPUTS: usize = {};", self.inputs).unwrap();
+        writeln!(code, "/// Number of outputs written by `Network::activate`").unwrap();
+        writeln!(code, "pub const OUTPUTS: usize = {};", self.outputs).unwrap();
+        writeln!(code, "const NEURONS: usize = {};", self.bias.len()).unwrap();
+        if !self.feed_forward {
+            writeln!(code, "const STEPS: usize = {};", self.steps).unwrap();
+            writeln!(code, "const STEP_SIZE: f64 = {:?};", STEP_SIZE).unwrap();
+        }
+        writeln!(
+            code,
+            "const OUTPUT_INDEXES: [usize; OUTPUTS] = [{}];",
+            join(&self.output_indexes)
+        )
+        .unwrap();
+        writeln!(
+            code,
+            "const BIAS: [f64; NEURONS] = [{}];",
+            join_f64(&self.bias)
+        )
+        .unwrap();
+        if !self.feed_forward {
+            writeln!(
+                code,
+                "const TAU: [f64; NEURONS] = [{}];",
+                join_f64(&self.tau)
+            )
+            .unwrap();
+        }
+        writeln!(
+            code,
+            "const ACTIVATIONS: [u8; NEURONS] = [{}];",
+            join(&self.activations)
+        )
+        .unwrap();
+        writeln!(code, "// (to, from, weight)").unwrap();
+        writeln!(
+            code,
+            "const CONNECTIONS: [(usize, usize, f64); {}] = [",
+            self.connections.len()
+        )
+        .unwrap();
+        for (to, from, weight) in &self.connections {
+            writeln!(code, "    ({}, {}, {:?}),", to, from, weight).unwrap();
+        }
+        writeln!(code, "];").unwrap();
+        code.push_str(
+            "
+fn activation(kind: u8, x: f64) -> f64 {
+    match kind {
+        0 => 1.0 / (1.0 + (-x).exp()),
+        1 => x.tanh(),
+        2 => x.max(0.0),
+        3 => (-x * x).exp(),
+        4 => x.sin(),
+        5 => {
+            if x > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        6 => x,
+        _ => x.abs(),
+    }
+}
+
+/// State of the neurons, kept between activations
+#[derive(Debug, Clone)]
+pub struct Network {
+    state: [f64; NEURONS],
+}
+
+impl Default for Network {
+    fn default() -> Network {
+        Network::new()
+    }
+}
+
+impl Network {
+    /// Network with every neuron at rest
+    pub fn new() -> Network {
+        Network {
+            state: [0.0; NEURONS],
+        }
+    }
+
+    /// Put every neuron back at rest
+    pub fn reset(&mut self) {
+        self.state = [0.0; NEURONS];
+    }
+
+    /// Feed the sensors to the network and write its outputs
+    pub fn activate(&mut self, inputs: &[f64], outputs: &mut [f64]) {
+        let mut external = [0.0; NEURONS];
+        for (value, input) in external.iter_mut().zip(inputs.iter().take(INPUTS)) {
+            *value = *input;
+        }
+",
+        );
+        if self.feed_forward {
+            code.push_str(
+                "        let mut state = external;
+        for &(to, from, weight) in CONNECTIONS.iter() {
+            state[to] += weight * activation(ACTIVATIONS[from], state[from] + BIAS[from]);
+        }
+        self.state = state;
+",
+            );
+        } else {
+            code.push_str(
+                "        for _ in 0..STEPS {
+            let mut current = [0.0; NEURONS];
+            for (neuron, value) in current.iter_mut().enumerate() {
+                *value = activation(ACTIVATIONS[neuron], self.state[neuron] + BIAS[neuron]);
+            }
+            let mut sum = [0.0; NEURONS];
+            for &(to, from, weight) in CONNECTIONS.iter() {
+                sum[to] += weight * current[from];
+            }
+            for (neuron, value) in self.state.iter_mut().enumerate() {
+                *value += STEP_SIZE * ((sum[neuron] - *value + external[neuron]) / TAU[neuron]);
+            }
+        }
+",
+            );
+        }
+        code.push_str(
+            "        for (output, index) in outputs.iter_mut().zip(OUTPUT_INDEXES.iter()) {
+            *output = self.state[*index];
+        }
+    }
+}
+",
+        );
+        code
+    }
+
+    /// C99 header and source, in this order, declaring a `<prefix>_t` state
+    /// with `<prefix>_reset` and `<prefix>_activate` functions. The header is
+    /// expected to be saved as `<prefix>.h`.
+    pub fn to_c(&self, prefix: &str) -> (String, String) {
+        let prefix = c_identifier(prefix);
+        let upper = prefix.to_uppercase();
+        // C forbids empty arrays
+        let dimension = |len: usize| cmp::max(len, 1);
+
+        let mut header = String::new();
+        writeln!(
+            header,
+            "/* Network generated by rustneat from an evolved organism. */"
+        )
+        .unwrap();
+        writeln!(header, "#ifndef {}_H", upper).unwrap();
+        writeln!(header, "#define {}_H", upper).unwrap();
+        writeln!(header).unwrap();
+        writeln!(header, "#include <stddef.h>").unwrap();
+        writeln!(header).unwrap();
+        writeln!(header, "#define {}_INPUTS {}", upper, self.inputs).unwrap();
+        writeln!(header, "#define {}_OUTPUTS {}", upper, self.outputs).unwrap();
+        writeln!(header).unwrap();
+        writeln!(
+            header,
+            "/* State of the neurons, kept between activations */"
+        )
+        .unwrap();
+        writeln!(header, "typedef struct {{").unwrap();
+        writeln!(header, "    double state[{}];", dimension(self.bias.len())).unwrap();
+        writeln!(header, "}} {}_t;", prefix).unwrap();
+        writeln!(header).unwrap();
+        writeln!(header, "/* Put every neuron at rest */").unwrap();
+        writeln!(header, "void {}_reset({}_t *network);", prefix, prefix).unwrap();
+        writeln!(header).unwrap();
+        writeln!(
+            header,
+            "/* Feed the sensors to the network and write its outputs */"
+        )
+        .unwrap();
+        writeln!(
+            header,
+            "void {}_activate({}_t *network, const double *inputs, size_t inputs_len, double *outputs);",
+            prefix, prefix
+        )
+        .unwrap();
+        writeln!(header).unwrap();
+        writeln!(header, "#endif").unwrap();
+
+        let mut source = String::new();
+        writeln!(
+            source,
+            "/* Network generated by rustneat from an evolved organism. */"
+        )
+        .unwrap();
+        writeln!(source, "#include <math.h>").unwrap();
+        writeln!(source, "#include \"{}.h\"", prefix).unwrap();
+        writeln!(source).unwrap();
+        writeln!(source, "#define NEURONS {}", self.bias.len()).unwrap();
+        writeln!(source, "#define CONNECTIONS {}", self.connections.len()).unwrap();
+        if !self.feed_forward {
+            writeln!(source, "#define STEPS {}", self.steps).unwrap();
+            writeln!(source, "#define STEP_SIZE {:?}", STEP_SIZE).unwrap();
+        }
+        writeln!(source).unwrap();
+        writeln!(
+            source,
+            "static const size_t output_indexes[{}] = {{{}}};",
+            dimension(self.outputs),
+            join_or_zero(&join(&self.output_indexes))
+        )
+        .unwrap();
+        writeln!(
+            source,
+            "static const double bias[{}] = {{{}}};",
+            dimension(self.bias.len()),
+            join_or_zero(&join_f64(&self.bias))
+        )
+        .unwrap();
+        if !self.feed_forward {
+            writeln!(
+                source,
+                "static const double tau[{}] = {{{}}};",
+                dimension(self.tau.len()),
+                join_or_zero(&join_f64(&self.tau))
+            )
+            .unwrap();
+        }
+        writeln!(
+            source,
+            "static const unsigned char activations[{}] = {{{}}};",
+            dimension(self.activations.len()),
+            join_or_zero(&join(&self.activations))
+        )
+        .unwrap();
+        writeln!(source, "static const struct {{").unwrap();
+        writeln!(source, "    size_t to;").unwrap();
+        writeln!(source, "    size_t from;").unwrap();
+        writeln!(source, "    double weight;").unwrap();
+        writeln!(
+            source,
+            "}} connections[{}] = {{",
+            dimension(self.connections.len())
+        )
+        .unwrap();
+        if self.connections.is_empty() {
+            writeln!(source, "    {{0, 0, 0.0}},").unwrap();
+        }
+        for (to, from, weight) in &self.connections {
+            writeln!(source, "    {{{}, {}, {:?}}},", to, from, weight).unwrap();
+        }
+        writeln!(source, "}};").unwrap();
+        source.push_str(
+            "
+static double activation(unsigned char kind, double x) {
+    switch (kind) {
+    case 0: return 1.0 / (1.0 + exp(-x));
+    case 1: return tanh(x);
+    case 2: return fmax(x, 0.0);
+    case 3: return exp(-x * x);
+    case 4: return sin(x);
+    case 5: return x > 0.0 ? 1.0 : 0.0;
+    case 6: return x;
+    default: return fabs(x);
+    }
+}
+",
+        );
+        let dimension = dimension(self.bias.len());
+        source.push_str(
+            &"
+void PREFIX_reset(PREFIX_t *network) {
+    for (size_t n = 0; n < NEURONS; n++) {
+        network->state[n] = 0.0;
+    }
+}
+
+void PREFIX_activate(PREFIX_t *network, const double *inputs, size_t inputs_len, double *outputs) {
+    double external[DIMENSION] = {0.0};
+    for (size_t n = 0; n < inputs_len && n < UPPER_INPUTS; n++) {
+        external[n] = inputs[n];
+    }
+"
+            .replace("PREFIX", &prefix)
+            .replace("UPPER", &upper)
+            .replace("DIMENSION", &dimension.to_string()),
+        );
+        if self.feed_forward {
+            source.push_str(
+                "    for (size_t c = 0; c < CONNECTIONS; c++) {
+        size_t from = connections[c].from;
+        external[connections[c].to] +=
+            connections[c].weight * activation(activations[from], external[from] + bias[from]);
+    }
+    for (size_t n = 0; n < NEURONS; n++) {
+        network->state[n] = external[n];
+    }
+",
+            );
+        } else {
+            source.push_str(
+                &"    for (size_t step = 0; step < STEPS; step++) {
+        double current[DIMENSION];
+        double sum[DIMENSION] = {0.0};
+        for (size_t n = 0; n < NEURONS; n++) {
+            current[n] = activation(activations[n], network->state[n] + bias[n]);
+        }
+        for (size_t c = 0; c < CONNECTIONS; c++) {
+            sum[connections[c].to] += connections[c].weight * current[connections[c].from];
+        }
+        for (size_t n = 0; n < NEURONS; n++) {
+            network->state[n] +=
+                STEP_SIZE * ((sum[n] - network->state[n] + external[n]) / tau[n]);
+        }
+    }
+"
+                .replace("DIMENSION", &dimension.to_string()),
+            );
+        }
+        source.push_str(
+            &"    for (size_t o = 0; o < UPPER_OUTPUTS; o++) {
+        outputs[o] = network->state[output_indexes[o]];
+    }
+}
+"
+            .replace("UPPER", &upper),
+        );
+        (header, source)
+    }
+
+    /// Python module using only the standard library, with a `Network` class
+    /// whose `activate(inputs)` returns the list of outputs
+    pub fn to_python(&self) -> String {
+        let mut code = String::new();
+        writeln!(
+            code,
+            "\"\"\"Network generated by rustneat from an evolved organism.\"\"\""
+        )
+        .unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "import math").unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "INPUTS = {}", self.inputs).unwrap();
+        writeln!(code, "OUTPUTS = {}", self.outputs).unwrap();
+        writeln!(code, "_NEURONS = {}", self.bias.len()).unwrap();
+        if !self.feed_forward {
+            writeln!(code, "_STEPS = {}", self.steps).unwrap();
+            writeln!(code, "_STEP_SIZE = {:?}", STEP_SIZE).unwrap();
+        }
+        writeln!(code, "_OUTPUT_INDEXES = [{}]", join(&self.output_indexes)).unwrap();
+        writeln!(code, "_BIAS = [{}]", join_f64(&self.bias)).unwrap();
+        if !self.feed_forward {
+            writeln!(code, "_TAU = [{}]", join_f64(&self.tau)).unwrap();
+        }
+        writeln!(code, "_ACTIVATIONS = [{}]", join(&self.activations)).unwrap();
+        writeln!(code, "# (to, from, weight)").unwrap();
+        writeln!(code, "_CONNECTIONS = [").unwrap();
+        for (to, from, weight) in &self.connections {
+            writeln!(code, "    ({}, {}, {:?}),", to, from, weight).unwrap();
+        }
+        writeln!(code, "]").unwrap();
+        code.push_str(
+            "
+
+def _sigmoid(x):
+    try:
+        return 1.0 / (1.0 + math.exp(-x))
+    except OverflowError:
+        return 0.0
+
+
+_FUNCTIONS = [
+    _sigmoid,
+    math.tanh,
+    lambda x: max(x, 0.0),
+    lambda x: math.exp(-x * x),
+    math.sin,
+    lambda x: 1.0 if x > 0.0 else 0.0,
+    lambda x: x,
+    abs,
+]
+
+
+class Network:
+    \"\"\"State of the neurons, kept between activations\"\"\"
+
+    def __init__(self):
+        self.reset()
+
+    def reset(self):
+        \"\"\"Put every neuron at rest\"\"\"
+        self.state = [0.0] * _NEURONS
+
+    def activate(self, inputs):
+        \"\"\"Feed the sensors to the network and return its outputs\"\"\"
+        external = [0.0] * _NEURONS
+        for n, value in enumerate(inputs[:INPUTS]):
+            external[n] = float(value)
+",
+        );
+        if self.feed_forward {
+            code.push_str(
+                "        state = external
+        for to, source, weight in _CONNECTIONS:
+            function = _FUNCTIONS[_ACTIVATIONS[source]]
+            state[to] += weight * function(state[source] + _BIAS[source])
+        self.state = state
+",
+            );
+        } else {
+            code.push_str(
+                "        state = self.state
+        for _ in range(_STEPS):
+            current = [
+                _FUNCTIONS[_ACTIVATIONS[n]](state[n] + _BIAS[n]) for n in range(_NEURONS)
+            ]
+            total = [0.0] * _NEURONS
+            for to, source, weight in _CONNECTIONS:
+                total[to] += weight * current[source]
+            for n in range(_NEURONS):
+                state[n] += _STEP_SIZE * ((total[n] - state[n] + external[n]) / _TAU[n])
+",
+            );
+        }
+        code.push_str("        return [self.state[index] for index in _OUTPUT_INDEXES]\n");
+        code
+    }
+
+    /// Compile or interpret the code generated for `language`, feed it the
+    /// `samples` in sequence and compare its outputs with the ones of
+    /// `Organism::activate` from a reset state. Samples are padded with zeros or
+    /// truncated to `inputs()` values. Outputs match when they differ by at most
+    /// `tolerance`, relative to the expected value when it is bigger than one.
+    ///
+    /// The tools are `rustc`, `cc` and `python3`, unless the `RUSTC`, `CC` or
+    /// `PYTHON` environment variables name others.
+    pub fn check(
+        &self,
+        language: TargetLanguage,
+        samples: &[Vec<f64>],
+        tolerance: f64,
+    ) -> Result<(), CodegenError> {
+        let samples = samples
+            .iter()
+            .map(|sample| {
+                let mut sample = sample.clone();
+                sample.resize(self.inputs, 0f64);
+                sample
+            })
+            .collect::<Vec<Vec<f64>>>();
+        let directory = env::temp_dir().join(format!(
+            "rustneat-codegen-{}-{}",
+            process::id(),
+            CHECK_DIRECTORY_COUNT.fetch_add(1, Ordering::SeqCst)
+        ));
+        fs::create_dir_all(&directory)?;
+        let found = self.run(language, &samples, &directory);
+        let _ = fs::remove_dir_all(&directory);
+        let found = found?;
+
+        let mut organism = self.organism.clone();
+        let mut expected = vec![0f64; self.outputs];
+        for (sample_index, sample) in samples.iter().enumerate() {
+            organism.activate(sample.clone(), &mut expected);
+            let line = found.get(sample_index);
+            for (output, expected) in expected.iter().enumerate() {
+                let found = line.and_then(|line| line.get(output)).cloned();
+                let matches = found.is_some_and(|found| {
+                    (found.is_nan() && expected.is_nan())
+                        || (found - expected).abs() <= tolerance * expected.abs().max(1f64)
+                });
+                if !matches {
+                    return Err(CodegenError::Mismatch {
+                        sample: sample_index,
+                        output,
+                        expected: *expected,
+                        found,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Outputs printed by the generated code for each sample
+    fn run(
+        &self,
+        language: TargetLanguage,
+        samples: &[Vec<f64>],
+        directory: &Path,
+    ) -> Result<Vec<Vec<f64>>, CodegenError> {
+        let program = directory.join(format!("check{}", env::consts::EXE_SUFFIX));
+        let stdout = match language {
+            TargetLanguage::Rust => {
+                fs::write(directory.join("network.rs"), self.to_rust())?;
+                let mut main = String::from("mod network;\n\nfn main() {\n");
+                main.push_str("    let mut network = network::Network::new();\n");
+                main.push_str("    let mut outputs = [0.0; network::OUTPUTS];\n");
+                for sample in samples {
+                    writeln!(
+                        main,
+                        "    network.activate(&[{}], &mut outputs);",
+                        join_f64(sample)
+                    )
+                    .unwrap();
+                    main.push_str(
+                        "    let line: Vec<String> = outputs.iter().map(|o| format!(\"{:?}\", o)).collect();\n",
+                    );
+                    main.push_str("    println!(\"{}\", line.join(\" \"));\n");
+                }
+                main.push_str("}\n");
+                fs::write(directory.join("main.rs"), main)?;
+                let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+                run_tool(
+                    Command::new(rustc)
+                        .current_dir(directory)
+                        .args(["--edition", "2018", "-O", "-o"])
+                        .arg(&program)
+                        .arg("main.rs"),
+                )?;
+                run_tool(Command::new(&program).current_dir(directory))?
+            }
+            TargetLanguage::C => {
+                let (header, source) = self.to_c("network");
+                fs::write(directory.join("network.h"), header)?;
+                fs::write(directory.join("network.c"), source)?;
+                let mut main = String::from("#include <stdio.h>\n#include \"network.h\"\n\n");
+                main.push_str("int main(void) {\n");
+                main.push_str("    network_t network;\n");
+                main.push_str("    double outputs[NETWORK_OUTPUTS + 1];\n");
+                main.push_str("    network_reset(&network);\n");
+                for sample in samples {
+                    main.push_str("    {\n");
+                    writeln!(
+                        main,
+                        "        const double inputs[] = {{{}}};",
+                        join_or_zero(&join_f64(sample))
+                    )
+                    .unwrap();
+                    writeln!(
+                        main,
+                        "        network_activate(&network, inputs, {}, outputs);",
+                        sample.len()
+                    )
+                    .unwrap();
+                    main.push_str("        for (size_t o = 0; o < NETWORK_OUTPUTS; o++) {\n");
+                    main.push_str("            printf(o ? \" %.17g\" : \"%.17g\", outputs[o]);\n");
+                    main.push_str("        }\n");
+                    main.push_str("        printf(\"\\n\");\n");
+                    main.push_str("    }\n");
+                }
+                main.push_str("    return 0;\n}\n");
+                fs::write(directory.join("main.c"), main)?;
+                let cc = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+                run_tool(
+                    Command::new(cc)
+                        .current_dir(directory)
+                        .args(["-std=c99", "-O2", "-o"])
+                        .arg(&program)
+                        .args(["main.c", "network.c", "-lm"]),
+                )?;
+                run_tool(Command::new(&program).current_dir(directory))?
+            }
+            TargetLanguage::Python => {
+                fs::write(directory.join("network.py"), self.to_python())?;
+                let mut main = String::from("from network import Network\n\n");
+                main.push_str("network = Network()\n");
+                main.push_str("for inputs in [\n");
+                for sample in samples {
+                    writeln!(main, "    [{}],", join_f64(sample)).unwrap();
+                }
+                main.push_str("]:\n");
+                main.push_str(
+                    "    print(\" \".join(repr(output) for output in network.activate(inputs)))\n",
+                );
+                fs::write(directory.join("main.py"), main)?;
+                let python = env::var("PYTHON").unwrap_or_else(|_| "python3".to_string());
+                run_tool(
+                    Command::new(python)
+                        .current_dir(directory)
+                        .arg("-B")
+                        .arg("main.py"),
+                )?
+            }
+        };
+        Ok(stdout
+            .lines()
+            .map(|line| {
+                line.split_whitespace()
+                    .map(|value| value.parse::<f64>().unwrap_or(f64::NAN))
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+fn run_tool(command: &mut Command) -> Result<String, CodegenError> {
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(CodegenError::Tool {
+            command: format!("{:?}", command),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn activation_kind(activation: Activation) -> usize {
+    Activation::ALL
+        .iter()
+        .position(|candidate| *candidate == activation)
+        .unwrap()
+}
+
+fn c_identifier(name: &str) -> String {
+    let mut identifier = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if identifier.is_empty() || identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+    identifier
+}
+
+fn join<T: fmt::Display>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// Debug keeps the decimal point, so the literals are floats in every language
+fn join_f64(values: &[f64]) -> String {
+    values
+        .iter()
+        .map(|value| format!("{:?}", value))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn join_or_zero(values: &str) -> String {
+    if values.is_empty() {
+        "0".to_string()
+    } else {
+        values.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gene::Gene;
+    use crate::genome::Genome;
+    use crate::node_gene::{NeuronRole, NodeGene};
+
+    const TOLERANCE: f64 = 1e-9;
+
+    fn recurrent_organism() -> Organism {
+        let mut genome = Genome::new_initialized(2, 1);
+        genome.add_node(NodeGene::new(3, NeuronRole::Hidden));
+        genome.add_gene(Gene::new(0, 3, 1.5f64, true));
+        genome.add_gene(Gene::new(3, 2, -0.75f64, true));
+        genome.add_gene(Gene::new(3, 3, 0.5f64, true));
+        genome.add_gene(Gene::new(2, 3, 0.25f64, true));
+        for (id, bias, tau, activation) in &[
+            (2, 0.1, 0.05, Activation::Tanh),
+            (3, -0.3, 0.02, Activation::Gaussian),
+        ] {
+            let mut node = *genome.get_node(*id).unwrap();
+            node.set_bias(*bias);
+            node.set_tau(*tau);
+            node.set_activation(*activation);
+            genome.add_node(node);
+        }
+        let mut organism = Organism::new(genome);
+        organism.step_time = 0.2;
+        organism
+    }
+
+    fn samples() -> Vec<Vec<f64>> {
+        vec![
+            vec![0f64, 1f64],
+            vec![1f64, 0f64],
+            vec![0.5, -0.5],
+            vec![2f64],
+        ]
+    }
+
+    fn feed_forward_organism() -> Organism {
+        let mut organism = Organism::new(Genome::new_initialized(2, 2));
+        organism
+            .genome
+            .add_node(NodeGene::new(4, NeuronRole::Hidden));
+        organism.genome.add_gene(Gene::new(1, 4, -2f64, true));
+        organism.genome.add_gene(Gene::new(4, 3, 0.5, true));
+        organism.feed_forward = true;
+        organism
+    }
+
+    fn organism_without_roles() -> Organism {
+        let mut genome = Genome::default();
+        genome.add_gene(Gene::new(0, 1, 1f64, true));
+        genome.add_gene(Gene::new(1, 1, -0.5f64, true));
+        Organism::new(genome)
+    }
+
+    #[test]
+    fn rust_code_should_inline_the_network() {
+        let code = Codegen::new(&recurrent_organism()).to_rust();
+        assert!(code.contains("pub const INPUTS: usize = 2;"));
+        assert!(code.contains("pub const OUTPUTS: usize = 1;"));
+        assert!(code.contains("const STEPS: usize = 20;"));
+        assert!(code.contains("const BIAS: [f64; NEURONS] = [0.0, 0.0, 0.1, -0.3];"));
+        assert!(code.contains("const ACTIVATIONS: [u8; NEURONS] = [0, 0, 1, 3];"));
+        assert!(code.contains("    (2, 3, -0.75),"));
+        assert!(code.contains("    (3, 2, 0.25),"));
+        assert!(code.contains("    (3, 0, 1.5),"));
+    }
+
+    #[test]
+    fn python_and_c_code_should_inline_the_network() {
+        let codegen = Codegen::new(&recurrent_organism());
+        let code = codegen.to_python();
+        assert!(code.contains("INPUTS = 2\n"));
+        assert!(code.contains("_STEPS = 20\n"));
+        assert!(code.contains("_BIAS = [0.0, 0.0, 0.1, -0.3]\n"));
+        assert!(code.contains("_ACTIVATIONS = [0, 0, 1, 3]\n"));
+        assert!(code.contains("    (2, 3, -0.75),"));
+        let (header, source) = codegen.to_c("network");
+        assert!(header.contains("#define NETWORK_OUTPUTS 1"));
+        assert!(source.contains("-0.75"));
+    }
+
+    #[test]
+    fn feed_forward_code_should_skip_the_simulation() {
+        let codegen = Codegen::new(&feed_forward_organism());
+        assert!(!codegen.to_rust().contains("STEPS"));
+        assert!(!codegen.to_python().contains("_STEPS"));
+    }
+
+    #[test]
+    fn genomes_without_roles_should_use_the_given_io() {
+        let codegen = Codegen::with_io(&organism_without_roles(), 1, 1);
+        assert_eq!((codegen.inputs(), codegen.outputs()), (1, 1));
+        assert!(codegen.to_python().contains("INPUTS = 1\n"));
+    }
+
+    #[test]
+    #[ignore = "runs rustc, cc and python3"]
+    fn generated_code_should_match_the_organism() {
+        for codegen in &[
+            Codegen::new(&recurrent_organism()),
+            Codegen::new(&feed_forward_organism()),
+            Codegen::with_io(&organism_without_roles(), 1, 1),
+        ] {
+            for language in &[
+                TargetLanguage::Rust,
+                TargetLanguage::C,
+                TargetLanguage::Python,
+            ] {
+                codegen.check(*language, &samples(), TOLERANCE).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "runs python3"]
+    fn check_should_report_mismatches() {
+        let codegen = Codegen::new(&recurrent_organism());
+        let mut other = recurrent_organism();
+        other.genome.get_gene_mut(0, 2).unwrap().set_weight(3f64);
+        let mut wrong = Codegen::new(&other);
+        wrong.organism = codegen.organism.clone();
+        match wrong.check(TargetLanguage::Python, &samples(), TOLERANCE) {
+            Err(CodegenError::Mismatch { sample: 0, .. }) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn c_identifiers_should_be_sanitized() {
+        assert_eq!(c_identifier("lunar-lander"), "lunar_lander");
+        assert_eq!(c_identifier("2d"), "_2d");
+        let (header, source) = Codegen::new(&recurrent_organism()).to_c("pilot");
+        assert!(header.contains("#define PILOT_INPUTS 2"));
+        assert!(header.contains("void pilot_activate(pilot_t *network"));
+        assert!(source.contains("#include \"pilot.h\""));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::mutation_config::MutationConfig;
+use serde_json::{Map, Value};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// What `MutationConfig::load` found in a file besides the settings
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigReport {
+    /// Settings missing from the file, which kept their default value
+    pub defaulted: Vec<String>,
+    /// Keys of the file that are not settings, ignored
+    pub unknown: Vec<String>,
+}
+
+fn invalid_data<E: ToString>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"))
+}
+
+impl MutationConfig {
+    /// Read a configuration from a TOML file if its extension is `.toml`, JSON
+    /// otherwise. Missing settings get their default value.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<(MutationConfig, ConfigReport)> {
+        MutationConfig::load_with_defaults(path, &MutationConfig::default())
+    }
+
+    /// Read a configuration like `load`, missing settings being taken from
+    /// `defaults`. Values out of range are rejected with
+    /// `io::ErrorKind::InvalidData`, see `MutationConfig::validate`.
+    pub fn load_with_defaults<P: AsRef<Path>>(
+        path: P,
+        defaults: &MutationConfig,
+    ) -> io::Result<(MutationConfig, ConfigReport)> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        let file: Value = if is_toml(path) {
+            toml::from_str(&content).map_err(invalid_data)?
+        } else {
+            serde_json::from_str(&content)?
+        };
+        MutationConfig::from_value(file, defaults)
+    }
+
+    fn from_value(
+        file: Value,
+        defaults: &MutationConfig,
+    ) -> io::Result<(MutationConfig, ConfigReport)> {
+        let file = match file {
+            Value::Object(file) => file,
+            _ => return Err(invalid_data("configuration is not a table of settings")),
+        };
+        let mut settings: Map<String, Value> = match serde_json::to_value(defaults)? {
+            Value::Object(settings) => settings,
+            _ => unreachable!("MutationConfig serializes to a map"),
+        };
+        let mut report = ConfigReport {
+            defaulted: settings
+                .keys()
+                .filter(|key| !file.contains_key(*key))
+                .cloned()
+                .collect(),
+            unknown: vec![],
+        };
+        for (key, value) in file {
+            if settings.contains_key(&key) {
+                settings.insert(key, value);
+            } else {
+                report.unknown.push(key);
+            }
+        }
+        let config: MutationConfig = serde_json::from_value(Value::Object(settings))?;
+        config.validate().map_err(invalid_data)?;
+        Ok((config, report))
+    }
+
+    /// Write every setting, as TOML if the extension of `path` is `.toml` and
+    /// JSON otherwise
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let content = if is_toml(path) {
+            toml::to_string_pretty(self).map_err(invalid_data)?
+        } else {
+            serde_json::to_string_pretty(self)?
+        };
+        fs::write(path, content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation::Activation;
+    use crate::crossover::CrossoverOperator;
+    use serde_json::json;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("rustneat-config-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn config_should_round_trip_in_both_formats() {
+        let config = MutationConfig::new()
+            .tau(0.1)
+            .max_stagnation(30)
+            .allowed_activations(vec![Activation::Tanh, Activation::Relu])
+            .crossover_operators(vec![
+                (CrossoverOperator::Blend { alpha: 0.5 }, 1f64),
+                (CrossoverOperator::Uniform, 2f64),
+            ])
+            .build();
+        for name in &["config.toml", "config.json"] {
+            let path = temp_path(name);
+            config.save(&path).unwrap();
+            let (loaded, report) = MutationConfig::load(&path).unwrap();
+            fs::remove_file(&path).unwrap();
+            assert_eq!(report, ConfigReport::default());
+            assert_eq!(format!("{:?}", loaded), format!("{:?}", config));
+        }
+    }
+
+    #[test]
+    fn missing_settings_should_be_reported() {
+        let path = temp_path("partial.toml");
+        fs::write(
+            &path,
+            "add_neuron_rate = 0.2\nstep_time = 1\nbest_fitness = 3.5\n",
+        )
+        .unwrap();
+        let defaults = MutationConfig::new().tau(0.5).build();
+        let (config, report) = MutationConfig::load_with_defaults(&path, &defaults).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!((config.add_neuron_rate - 0.2).abs() < f64::EPSILON);
+        assert!((config.step_time - 1f64).abs() < f64::EPSILON);
+        assert!((config.tau - 0.5).abs() < f64::EPSILON);
+        assert_eq!(report.unknown, vec!["best_fitness"]);
+        assert!(report.defaulted.contains(&"tau".to_string()));
+        assert!(!report.defaulted.contains(&"step_time".to_string()));
+    }
+
+    #[test]
+    fn out_of_range_settings_should_be_rejected() {
+        for file in &[
+            json!({ "add_connection_rate": 1.5 }),
+            json!({ "tau": 0.0 }),
+            json!({ "tau_min": 2.0, "tau_max": 1.0 }),
+            json!([]),
+        ] {
+            let error =
+                MutationConfig::from_value(file.clone(), &MutationConfig::default()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
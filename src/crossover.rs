@@ -0,0 +1,207 @@
+use crate::gene::Gene;
+use crate::genome::Genome;
+use crate::mutation_config::MutationConfig;
+use crate::rng;
+use rand::Rng;
+
+/// Reproduction scheme that builds a child genome out of several parents
+pub trait Crossover {
+    /// Child of `parents`, the fittest parent first. Implementations usually
+    /// build on `Genome::crossover_genes`.
+    fn crossover(&self, parents: &[&Genome], config: &MutationConfig) -> Genome;
+
+    /// Number of parents the operator wants
+    fn parents(&self) -> usize {
+        2
+    }
+}
+
+/// Built-in crossover operators, selectable and weighted through
+/// `MutationConfig::crossover_operators`. Every operator takes disjoint and excess
+/// genes from the fittest parent and only differs on matching genes.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CrossoverOperator {
+    /// Each matching gene comes from a random parent
+    Uniform,
+    /// Matching genes get the average weight of the parents
+    Average,
+    /// Matching genes get a random weight on the line through both parent weights,
+    /// extended by `alpha` times their distance on each side (BLX-α)
+    Blend {
+        /// Extension of the weight interval, 0 keeps weights between the parents.
+        /// Negative values count as 0.
+        alpha: f64,
+    },
+    /// Matching genes below a random innovation number come from the fittest
+    /// parent, the others from the second parent
+    SinglePoint,
+    /// Each matching gene comes from a random parent out of `parents` parents
+    MultiParent {
+        /// Number of parents, at least 2
+        parents: usize,
+    },
+}
+
+impl CrossoverOperator {
+    /// Pick an operator at random, proportionally to its weight
+    pub fn choose(operators: &[(CrossoverOperator, f64)]) -> Option<CrossoverOperator> {
+        let total = operators
+            .iter()
+            .map(|(_, weight)| weight.max(0f64))
+            .sum::<f64>();
+        if total <= 0f64 {
+            return None;
+        }
+        let mut selected = rng::random::<f64>() * total;
+        for (operator, weight) in operators {
+            if selected < weight.max(0f64) {
+                return Some(*operator);
+            }
+            selected -= weight.max(0f64);
+        }
+        operators
+            .iter()
+            .rev()
+            .find(|(_, weight)| *weight > 0f64)
+            .map(|(operator, _)| *operator)
+    }
+}
+
+impl Crossover for CrossoverOperator {
+    fn crossover(&self, parents: &[&Genome], config: &MutationConfig) -> Genome {
+        let (fittest, others) = parents
+            .split_first()
+            .expect("crossover needs at least one parent");
+        let disable_rate = config.crossover_disable_rate;
+        let mut rng = rng::thread_rng();
+        match *self {
+            CrossoverOperator::Uniform | CrossoverOperator::MultiParent { .. } => fittest
+                .crossover_genes(others, disable_rate, |genes| {
+                    *genes[rng.gen_range(0, genes.len())]
+                }),
+            CrossoverOperator::Average => fittest.crossover_genes(others, disable_rate, |genes| {
+                let mut gene = *genes[0];
+                let total = genes.iter().map(|gene| gene.weight()).sum::<f64>();
+                gene.set_weight(total / genes.len() as f64);
+                gene
+            }),
+            CrossoverOperator::Blend { alpha } => {
+                let alpha = alpha.max(0f64);
+                fittest.crossover_genes(others, disable_rate, |genes| {
+                    let mut gene = *genes[0];
+                    let other = genes[rng.gen_range(1, genes.len())];
+                    let ratio = rng.gen_range(-alpha, 1f64 + alpha);
+                    gene.set_weight(ratio * gene.weight() + (1f64 - ratio) * other.weight());
+                    gene
+                })
+            }
+            CrossoverOperator::SinglePoint => {
+                let last_innovation = fittest
+                    .get_genes()
+                    .iter()
+                    .map(Gene::innovation)
+                    .max()
+                    .unwrap_or(0);
+                let cut = rng.gen_range(0, last_innovation + 1);
+                fittest.crossover_genes(others, disable_rate, |genes| {
+                    if genes[0].innovation() < cut {
+                        *genes[0]
+                    } else {
+                        *genes[1]
+                    }
+                })
+            }
+        }
+    }
+
+    fn parents(&self) -> usize {
+        match *self {
+            CrossoverOperator::MultiParent { parents } => parents.max(2),
+            _ => 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parents() -> (Genome, Genome) {
+        let mut genome1 = Genome::default();
+        genome1.add_gene(Gene::new(0, 1, 1f64, true));
+        genome1.add_gene(Gene::new(0, 2, 1f64, true));
+        let mut genome2 = Genome::default();
+        genome2.add_gene(Gene::new(0, 1, 3f64, true));
+        (genome1, genome2)
+    }
+
+    #[test]
+    fn average_crossover_should_average_matching_weights() {
+        let (genome1, genome2) = parents();
+        let child =
+            CrossoverOperator::Average.crossover(&[&genome1, &genome2], &MutationConfig::default());
+        assert_eq!(child.total_genes(), 2);
+        assert!((child.total_weights() - 3f64).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn blend_crossover_should_stay_in_extended_interval() {
+        let (genome1, genome2) = parents();
+        for _ in 0..50 {
+            let child = CrossoverOperator::Blend { alpha: 0.5 }
+                .crossover(&[&genome1, &genome2], &MutationConfig::default());
+            let weight = child.get_genes()[0].weight();
+            assert!((-1e-9..=4f64 + 1e-9).contains(&weight), "{}", weight);
+        }
+    }
+
+    #[test]
+    fn blend_crossover_should_treat_negative_alpha_as_zero() {
+        let (genome1, genome2) = parents();
+        for _ in 0..50 {
+            let child = CrossoverOperator::Blend { alpha: -2f64 }
+                .crossover(&[&genome1, &genome2], &MutationConfig::default());
+            let weight = child.get_genes()[0].weight();
+            assert!((1f64 - 1e-9..=3f64 + 1e-9).contains(&weight), "{}", weight);
+        }
+    }
+
+    #[test]
+    fn multi_parent_crossover_should_take_genes_from_every_parent() {
+        let (genome1, genome2) = parents();
+        let mut genome3 = Genome::default();
+        genome3.add_gene(Gene::new(0, 1, 5f64, true));
+        let operator = CrossoverOperator::MultiParent { parents: 3 };
+        assert_eq!(operator.parents(), 3);
+        let weights: Vec<f64> = (0..100)
+            .map(|_| {
+                operator
+                    .crossover(&[&genome1, &genome2, &genome3], &MutationConfig::default())
+                    .get_genes()[0]
+                    .weight()
+            })
+            .collect();
+        for weight in &[1f64, 3f64, 5f64] {
+            assert!(weights.contains(weight));
+        }
+    }
+
+    #[test]
+    fn choose_should_respect_weights() {
+        let operators = [
+            (CrossoverOperator::Uniform, 0f64),
+            (CrossoverOperator::SinglePoint, 1f64),
+        ];
+        for _ in 0..20 {
+            assert_eq!(
+                CrossoverOperator::choose(&operators),
+                Some(CrossoverOperator::SinglePoint)
+            );
+        }
+        assert_eq!(
+            CrossoverOperator::choose(&[(CrossoverOperator::Uniform, 0f64)]),
+            None
+        );
+    }
+}
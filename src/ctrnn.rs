@@ -1,3 +1,4 @@
+use crate::activation::Activation;
 use rulinalg::matrix::{BaseMatrix, BaseMatrixMut, Matrix};
 
 #[cfg(feature = "ctrnn_telemetry")]
@@ -9,11 +10,12 @@ use serde_json;
 #[allow(missing_docs)]
 #[derive(Debug)]
 pub struct CtrnnNeuralNetwork<'a> {
-    pub y: &'a [f64],     //current state of neuron(j)
+    pub y: &'a [f64],                  //current state of neuron(j)
     pub tau: &'a [f64], //τ - time constant ( t > 0 ). The neuron's speed of response to an external sensory signal. Membrane resistance time.
     pub wji: &'a [f64], //w - weights of the connection from neuron(j) to neuron(i)
     pub theta: &'a [f64], //θ - bias of the neuron(j)
     pub i: &'a [f64],   //I - external input to neuron(i)
+    pub activations: &'a [Activation], //σ - activation function of the neuron(j)
 }
 
 #[allow(missing_docs)]
@@ -34,7 +36,7 @@ impl Ctrnn {
         Ctrnn::telemetry(&y);
 
         for _ in 0..steps {
-            let current_weights = (&y + &theta).apply(&Ctrnn::sigmoid);
+            let current_weights = Ctrnn::activate_neurons(&y + &theta, nn.activations);
             y = &y
                 + ((&wji * current_weights) - &y + &i)
                     .elediv(&tau)
@@ -50,6 +52,17 @@ impl Ctrnn {
         1f64 / (1f64 + (-x).exp())
     }
 
+    fn activate_neurons(values: Matrix<f64>, activations: &[Activation]) -> Matrix<f64> {
+        let rows = values.rows();
+        let activated: Vec<f64> = values
+            .into_vec()
+            .into_iter()
+            .zip(activations.iter())
+            .map(|(value, activation)| activation.activate(value))
+            .collect();
+        Matrix::new(rows, 1, activated)
+    }
+
     fn vector_to_column_matrix(vector: &[f64]) -> Matrix<f64> {
         Matrix::new(vector.len(), 1, vector)
     }
@@ -71,6 +84,7 @@ impl Ctrnn {
 }
 
 #[cfg(test)]
+#[allow(clippy::neg_cmp_op_on_partial_ord)]
 mod tests {
     use super::*;
     macro_rules! assert_delta_vector {
@@ -96,6 +110,7 @@ mod tests {
         ];
         let theta = vec![-0.695126, -0.677891, -0.072129];
         let i = vec![0.98856, 0.31540, 0.0];
+        let activations = vec![Activation::Sigmoid; 3];
 
         let nn = CtrnnNeuralNetwork {
             y: &gamma,
@@ -103,6 +118,7 @@ mod tests {
             wji: &wji,
             theta: &theta,
             i: &i,
+            activations: &activations,
         };
 
         let ctrnn = Ctrnn::default();
@@ -146,4 +162,20 @@ mod tests {
             0.00000000000000000001
         );
     }
+
+    #[test]
+    fn each_neuron_should_use_its_own_activation() {
+        let nn = CtrnnNeuralNetwork {
+            y: &[-1.0, -1.0, 0.0],
+            tau: &[1.0, 1.0, 1.0],
+            wji: &[0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0],
+            theta: &[0.0, 0.0, 0.0],
+            i: &[-1.0, -1.0, 0.0],
+            activations: &[Activation::Sigmoid, Activation::Relu, Activation::Identity],
+        };
+
+        // neuron 2 reads sigmoid(-1) from neuron 0 and relu(-1) = 0 from neuron 1
+        let y = Ctrnn::default().activate_nn(0.1, 0.1, &nn);
+        assert_delta_vector!(y, vec![-1.0, -1.0, 0.1 * Ctrnn::sigmoid(-1.0)], 1e-12);
+    }
 }
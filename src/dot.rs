@@ -0,0 +1,137 @@
+use crate::genome::Genome;
+use crate::node_gene::{NeuronRole, NodeGene};
+use std::fmt::Write;
+
+pub(crate) const POSITIVE_COLOR: &str = "2166ac";
+pub(crate) const NEGATIVE_COLOR: &str = "b2182b";
+const DISABLED_COLOR: &str = "#999999";
+const MIN_PEN_WIDTH: f64 = 0.5;
+const MAX_PEN_WIDTH: f64 = 4.0;
+
+impl Genome {
+    /// Graphviz description of the network, to render with `dot -Tsvg`.
+    ///
+    /// Input neurons are ranked first and output neurons last, hidden neurons are
+    /// laid out between them. Edges are blue for positive and red for negative
+    /// weights, thicker and more opaque the bigger the weight compared to the
+    /// biggest weight of the genome. Disabled genes are dashed and grey, and
+    /// neurons with a bias show it under their id.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph genome {{").unwrap();
+        writeln!(dot, "  rankdir=LR;").unwrap();
+        writeln!(dot, "  node [shape=circle, fontsize=10];").unwrap();
+
+        let nodes_with_role = |role| {
+            self.get_nodes()
+                .iter()
+                .filter(move |node| node.role() == role)
+                .collect::<Vec<&NodeGene>>()
+        };
+        if self.has_io_roles() {
+            for (rank, role) in &[("source", NeuronRole::Input), ("sink", NeuronRole::Output)] {
+                writeln!(dot, "  {{").unwrap();
+                writeln!(dot, "    rank={};", rank).unwrap();
+                for node in nodes_with_role(*role) {
+                    writeln!(dot, "    {}", node_statement(node)).unwrap();
+                }
+                writeln!(dot, "  }}").unwrap();
+            }
+            for node in nodes_with_role(NeuronRole::Hidden) {
+                writeln!(dot, "  {}", node_statement(node)).unwrap();
+            }
+        } else {
+            for node in self.get_nodes() {
+                writeln!(dot, "  {}", node_statement(node)).unwrap();
+            }
+        }
+
+        let max_weight = self
+            .get_genes()
+            .iter()
+            .map(|gene| gene.weight().abs())
+            .fold(0f64, f64::max);
+        for gene in self.get_genes() {
+            let magnitude = if max_weight > 0f64 {
+                gene.weight().abs() / max_weight
+            } else {
+                0f64
+            };
+            let pen_width = MIN_PEN_WIDTH + (MAX_PEN_WIDTH - MIN_PEN_WIDTH) * magnitude;
+            let (color, style) = if gene.enabled() {
+                let rgb = if gene.weight() < 0f64 {
+                    NEGATIVE_COLOR
+                } else {
+                    POSITIVE_COLOR
+                };
+                let alpha = (64f64 + 191f64 * magnitude).round() as u8;
+                (format!("#{}{:02x}", rgb, alpha), "solid")
+            } else {
+                (DISABLED_COLOR.to_string(), "dashed")
+            };
+            writeln!(
+                dot,
+                "  {} -> {} [label=\"{:.2}\", color=\"{}\", penwidth={:.2}, style={}];",
+                gene.in_neuron_id(),
+                gene.out_neuron_id(),
+                gene.weight(),
+                color,
+                pen_width,
+                style
+            )
+            .unwrap();
+        }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
+
+fn node_statement(node: &NodeGene) -> String {
+    let shape = match node.role() {
+        NeuronRole::Input => "box",
+        NeuronRole::Output => "doublecircle",
+        NeuronRole::Hidden => "circle",
+    };
+    let label = if node.bias() == 0f64 {
+        node.id().to_string()
+    } else {
+        format!("{}\\nb={:+.2}", node.id(), node.bias())
+    };
+    format!("{} [label=\"{}\", shape={}];", node.id(), label, shape)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gene::Gene;
+
+    #[test]
+    fn dot_should_rank_neurons_and_style_genes() {
+        let mut genome = Genome::new_initialized(2, 1);
+        genome.add_node(NodeGene::new(3, NeuronRole::Hidden));
+        genome.add_gene(Gene::new(0, 3, -2f64, false));
+        let mut node = *genome.get_node(3).unwrap();
+        node.set_bias(0.5);
+        genome.add_node(node);
+
+        let dot = genome.to_dot();
+        assert!(dot.starts_with("digraph genome {"));
+        assert!(dot.contains("rank=source;\n    0 [label=\"0\", shape=box];"));
+        assert!(dot.contains("rank=sink;\n    2 [label=\"2\", shape=doublecircle];"));
+        assert!(dot.contains("3 [label=\"3\\nb=+0.50\", shape=circle];"));
+        assert!(dot
+            .contains("0 -> 3 [label=\"-2.00\", color=\"#999999\", penwidth=4.00, style=dashed];"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn dot_should_color_weights_by_sign() {
+        let mut genome = Genome::default();
+        genome.add_gene(Gene::new(0, 1, 1f64, true));
+        genome.add_gene(Gene::new(1, 0, -0.5f64, true));
+        let dot = genome.to_dot();
+        assert!(dot.contains("0 -> 1 [label=\"1.00\", color=\"#2166acff\", penwidth=4.00"));
+        assert!(dot.contains("1 -> 0 [label=\"-0.50\", color=\"#b2182ba0\", penwidth=2.25"));
+        assert!(!dot.contains("rank="));
+    }
+}
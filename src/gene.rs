@@ -1,17 +1,19 @@
-extern crate rand;
-
+use crate::rng;
 use rand::Closed01;
 use std::cmp::Ordering;
 
 /// A connection Gene
 #[derive(Debug, Copy, Clone)]
-#[cfg_attr(feature = "telemetry", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Gene {
     in_neuron_id: usize,
     out_neuron_id: usize,
     weight: f64,
     enabled: bool,
-    is_bias: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    innovation: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    frozen: bool,
 }
 
 impl Eq for Gene {}
@@ -48,39 +50,34 @@ impl PartialOrd for Gene {
 
 impl Gene {
     /// Create a new gene
-    pub fn new(
-        in_neuron_id: usize,
-        out_neuron_id: usize,
-        weight: f64,
-        enabled: bool,
-        is_bias: bool,
-    ) -> Gene {
+    pub fn new(in_neuron_id: usize, out_neuron_id: usize, weight: f64, enabled: bool) -> Gene {
         Gene {
-            in_neuron_id: in_neuron_id,
-            out_neuron_id: out_neuron_id,
-            weight: weight,
-            enabled: enabled,
-            is_bias: is_bias,
+            in_neuron_id,
+            out_neuron_id,
+            weight,
+            enabled,
+            innovation: 0,
+            frozen: false,
         }
     }
 
     /// Create a new gene with a specific connection
     pub fn new_connection(in_neuron_id: usize, out_neuron_id: usize) -> Gene {
         Gene {
-            in_neuron_id: in_neuron_id,
-            out_neuron_id: out_neuron_id,
+            in_neuron_id,
+            out_neuron_id,
             ..Gene::default()
         }
     }
 
     /// Generate a weight in [-1, 1]
     pub fn generate_weight() -> f64 {
-        rand::random::<Closed01<f64>>().0 * 2f64 - 1f64
+        rng::random::<Closed01<f64>>().0 * 2f64 - 1f64
     }
 
     /// Generate a weight in [-range, range]
     pub fn generate_weight_in_range(range: f64) -> f64 {
-        rand::random::<Closed01<f64>>().0 * 2.0 * range - range
+        rng::random::<Closed01<f64>>().0 * 2.0 * range - range
     }
     /// Connection in ->
     pub fn in_neuron_id(&self) -> usize {
@@ -110,13 +107,28 @@ impl Gene {
     pub fn set_disabled(&mut self) {
         self.enabled = false;
     }
-    /// Get if gene is bias
-    pub fn is_bias(&self) -> bool {
-        self.is_bias
+    /// Historical marking shared by every gene created by the same structural
+    /// mutation. Zero means the gene was created outside an `InnovationTracker`.
+    pub fn innovation(&self) -> usize {
+        self.innovation
+    }
+    /// Set the historical marking of this gene
+    pub fn set_innovation(&mut self, innovation: usize) {
+        self.innovation = innovation;
+    }
+    /// Frozen genes are left untouched by mutations and crossover
+    pub fn frozen(&self) -> bool {
+        self.frozen
+    }
+    /// Freeze or unfreeze the gene
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen;
     }
-    /// Set or disable bias
-    pub fn set_bias(&mut self, is_bias: bool) {
-        self.is_bias = is_bias
+    /// Two genes are the same innovation if they connect the same neurons and
+    /// share the historical marking. Untracked genes (innovation 0) thus only
+    /// match other untracked genes of the same connection.
+    pub fn is_same_innovation(&self, other: &Gene) -> bool {
+        self == other && self.innovation == other.innovation
     }
 }
 
@@ -127,7 +139,8 @@ impl Default for Gene {
             out_neuron_id: 1,
             weight: Gene::generate_weight(),
             enabled: true,
-            is_bias: false,
+            innovation: 0,
+            frozen: false,
         }
     }
 }
@@ -144,9 +157,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn same_innovation_should_need_the_same_connection_and_marking() {
+        let mut tracked = g(0, 1);
+        tracked.set_innovation(3);
+        let mut same = g(0, 1);
+        same.set_innovation(3);
+        let mut other_connection = g(0, 2);
+        other_connection.set_innovation(3);
+        assert!(tracked.is_same_innovation(&same));
+        assert!(!tracked.is_same_innovation(&other_connection));
+        assert!(!tracked.is_same_innovation(&g(0, 1)));
+        assert!(g(0, 1).is_same_innovation(&g(0, 1)));
+        assert!(!g(0, 1).is_same_innovation(&g(1, 0)));
+    }
+
     #[test]
     fn should_be_able_to_binary_search_for_a_gene() {
-        let mut genome = vec![g(0, 1), g(0, 2), g(3, 2), g(2, 3), g(1, 5)];
+        let mut genome = [g(0, 1), g(0, 2), g(3, 2), g(2, 3), g(1, 5)];
         genome.sort();
         genome.binary_search(&g(0, 1)).unwrap();
         genome.binary_search(&g(0, 2)).unwrap();
@@ -1,14 +1,60 @@
+use crate::activation::Activation;
+use crate::crossover::{Crossover, CrossoverOperator};
 use crate::gene::Gene;
-use crate::mutation::Mutation;
-use rand::{self, Closed01};
+use crate::genome_diff::GenomeDiff;
+use crate::genome_error::GenomeError;
+use crate::innovation::InnovationTracker;
+use crate::mutation::{Mutation, MutationRegistry};
+use crate::mutation_config::{MutationConfig, RecurrencePolicy};
+use crate::node_gene::{NeuronRole, NodeGene};
+use crate::rng;
+use rand::{Closed01, Rng};
 use std::cmp;
+use std::collections::{HashMap, HashSet};
 
-/// Vector of Genes
+/// Vector of Genes and the neurons they connect
 /// Holds a count of last neuron added, similar to Innovation number
+/// Genomes created with input and output neurons keep their counts, so structural
+/// mutations never connect into an input neuron or out of an output neuron.
+/// Deserialized genomes are checked with `validate`. Their recurrence policy is
+/// checked by the champion or checkpoint holding them, which know the policy.
 #[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedGenome"))]
 pub struct Genome {
     genes: Vec<Gene>,
+    nodes: Vec<NodeGene>,
     last_neuron_id: usize,
+    input_neurons: usize,
+    output_neurons: usize,
+}
+
+/// Genome as read by serde, before its invariants are checked
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedGenome {
+    genes: Vec<Gene>,
+    nodes: Vec<NodeGene>,
+    last_neuron_id: usize,
+    input_neurons: usize,
+    output_neurons: usize,
+}
+
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<UncheckedGenome> for Genome {
+    type Error = GenomeError;
+
+    fn try_from(unchecked: UncheckedGenome) -> Result<Genome, GenomeError> {
+        let genome = Genome {
+            genes: unchecked.genes,
+            nodes: unchecked.nodes,
+            last_neuron_id: unchecked.last_neuron_id,
+            input_neurons: unchecked.input_neurons,
+            output_neurons: unchecked.output_neurons,
+        };
+        genome.validate()?;
+        Ok(genome)
+    }
 }
 
 pub(crate) const MUTATE_CONNECTION_WEIGHT: f64 = 0.90f64;
@@ -16,15 +62,18 @@ pub(crate) const MUTATE_ADD_CONNECTION: f64 = 0.005f64;
 pub(crate) const MUTATE_ADD_NEURON: f64 = 0.004f64;
 pub(crate) const MUTATE_TOGGLE_EXPRESSION: f64 = 0.001f64;
 pub(crate) const MUTATE_CONNECTION_WEIGHT_PERTURBED_PROBABILITY: f64 = 0.90f64;
-pub(crate) const MUTATE_TOGGLE_BIAS: f64 = 0.01;
+pub(crate) const MUTATE_BIAS: f64 = 0.1;
+pub(crate) const MUTATE_BIAS_REPLACE_PROBABILITY: f64 = 0.1;
+pub(crate) const MUTATE_ACTIVATION: f64 = 0.01;
 pub(crate) const COMPATIBILITY_THRESHOLD: f64 = 3f64;
 
 impl Genome {
-    /// Create a genome from serialized gene data
+    /// Create a genome from serialized gene data.
+    /// Genes are not validated, use `try_from_genes` for untrusted data.
     pub fn from_genes(genes: Vec<Gene>, last_neuron_id: usize) -> Genome {
         let mut genome = Genome {
-            genes: Vec::new(),
             last_neuron_id,
+            ..Genome::default()
         };
         for gene in genes {
             // Directly add gene without validation since we're reconstructing
@@ -32,17 +81,63 @@ impl Genome {
                 Ok(pos) => genome.genes[pos].set_enabled(),
                 Err(_) => genome.genes.push(gene),
             }
+            genome.ensure_node(gene.in_neuron_id());
+            genome.ensure_node(gene.out_neuron_id());
         }
         genome.genes.sort();
         genome
     }
 
-    ///Add initial input and output neurons interconnected
+    /// Create a genome from serialized gene data, rejecting duplicate genes,
+    /// neuron ids bigger than `last_neuron_id` and non finite weights
+    pub fn try_from_genes(genes: Vec<Gene>, last_neuron_id: usize) -> Result<Genome, GenomeError> {
+        let mut connections = HashSet::new();
+        for gene in &genes {
+            for neuron_id in &[gene.in_neuron_id(), gene.out_neuron_id()] {
+                if *neuron_id > last_neuron_id {
+                    return Err(GenomeError::NeuronIdOutOfRange {
+                        neuron_id: *neuron_id,
+                        last_neuron_id,
+                    });
+                }
+            }
+            if !connections.insert((gene.in_neuron_id(), gene.out_neuron_id())) {
+                return Err(GenomeError::DuplicateGene {
+                    in_neuron_id: gene.in_neuron_id(),
+                    out_neuron_id: gene.out_neuron_id(),
+                });
+            }
+        }
+        let genome = Genome::from_genes(genes, last_neuron_id);
+        genome.validate()?;
+        Ok(genome)
+    }
+
+    fn with_io_nodes(input_neurons: usize, output_neurons: usize) -> Genome {
+        let mut genome = Genome {
+            input_neurons,
+            output_neurons,
+            ..Genome::default()
+        };
+        for i in 0..input_neurons {
+            genome.add_node(NodeGene::new(i, NeuronRole::Input));
+        }
+        for o in 0..output_neurons {
+            genome.add_node(NodeGene::new(input_neurons + o, NeuronRole::Output));
+        }
+        genome
+    }
+
+    ///Add initial input and output neurons interconnected.
+    /// Initial connections are numbered so every genome with the same number of
+    /// inputs and outputs shares their innovation numbers.
     pub fn new_initialized(input_neurons: usize, output_neurons: usize) -> Genome {
-        let mut genome = Genome::default();
+        let mut genome = Genome::with_io_nodes(input_neurons, output_neurons);
         for i in 0..input_neurons {
             for o in 0..output_neurons {
-                genome.add_gene(Gene::new_connection(i, input_neurons + o));
+                let mut gene = Gene::new_connection(i, input_neurons + o);
+                gene.set_innovation(i * output_neurons + o + 1);
+                genome.add_gene(gene);
             }
         }
         genome
@@ -51,91 +146,158 @@ impl Genome {
     /// Create genome with input/output neuron IDs but NO connections.
     /// NEAT will discover connections through mutation.
     pub fn new_unconnected(input_neurons: usize, output_neurons: usize) -> Genome {
-        Genome {
-            genes: Vec::new(),
-            last_neuron_id: if input_neurons + output_neurons > 0 {
-                input_neurons + output_neurons - 1
-            } else {
-                0
-            },
-        }
+        Genome::with_io_nodes(input_neurons, output_neurons)
     }
 
     /// May add a connection &| neuron &| mutat connection weight &|
     /// enable/disable connection
     pub fn mutate(&mut self) {
-        self.mutate_with_config(&crate::mutation_config::MutationConfig::default());
+        self.mutate_with_config(&MutationConfig::default());
     }
 
     /// Mutate using specific mutation rates from config
-    pub fn mutate_with_config(&mut self, config: &crate::mutation_config::MutationConfig) {
-        if rand::random::<Closed01<f64>>().0 < config.add_connection_rate || self.genes.is_empty() {
-            self.mutate_add_connection_with_config(config);
+    pub fn mutate_with_config(&mut self, config: &MutationConfig) {
+        let mut tracker = InnovationTracker::from_genomes(Some(&*self));
+        self.mutate_with_tracker(config, &mut tracker);
+    }
+
+    /// Mutate using specific mutation rates from config, taking innovation
+    /// numbers and new neuron ids from a tracker shared by the population
+    pub fn mutate_with_tracker(
+        &mut self,
+        config: &MutationConfig,
+        tracker: &mut InnovationTracker,
+    ) {
+        if rng::random::<Closed01<f64>>().0 < config.add_connection_rate || self.genes.is_empty() {
+            self.mutate_add_connection_with_config(config, tracker);
+        };
+
+        if rng::random::<Closed01<f64>>().0 < config.add_neuron_rate {
+            self.mutate_add_neuron_with_config(config, tracker);
         };
 
-        if rand::random::<Closed01<f64>>().0 < config.add_neuron_rate {
-            self.mutate_add_neuron();
+        if rng::random::<Closed01<f64>>().0 < config.delete_connection_rate {
+            self.mutate_delete_connection();
         };
 
-        if rand::random::<Closed01<f64>>().0 < config.weight_mutation_rate {
+        if rng::random::<Closed01<f64>>().0 < config.delete_neuron_rate {
+            self.mutate_delete_neuron();
+        };
+
+        if rng::random::<Closed01<f64>>().0 < config.weight_mutation_rate {
             self.mutate_connection_weight_with_config(config);
         };
 
-        if rand::random::<Closed01<f64>>().0 < config.toggle_expression_rate {
+        if rng::random::<Closed01<f64>>().0 < config.toggle_expression_rate {
             self.mutate_toggle_expression();
         };
 
-        if rand::random::<Closed01<f64>>().0 < config.toggle_bias_rate {
-            self.mutate_toggle_bias();
+        if rng::random::<Closed01<f64>>().0 < config.bias_mutation_rate {
+            self.mutate_bias_with_config(config);
+        };
+
+        if rng::random::<Closed01<f64>>().0 < config.activation_mutation_rate {
+            self.mutate_activation(&config.allowed_activations);
+        };
+
+        if rng::random::<Closed01<f64>>().0 < config.tau_mutation_rate {
+            self.mutate_tau(config);
         };
     }
 
+    /// Mutate with the built-in mutations of `config`, then with the operators
+    /// of `registry`
+    pub fn mutate_with_registry(
+        &mut self,
+        config: &MutationConfig,
+        tracker: &mut InnovationTracker,
+        registry: &MutationRegistry,
+    ) {
+        self.mutate_with_tracker(config, tracker);
+        if !registry.is_empty() {
+            registry.apply(self, config, tracker);
+            self.enforce_recurrence_policy(config.effective_recurrence_policy());
+        }
+    }
+
     /// Mate two genes
     pub fn mate(&self, other: &Genome, fittest: bool) -> Genome {
-        if fittest {
-            self.mate_genes(other)
+        self.mate_with_config(other, fittest, &MutationConfig::default())
+    }
+
+    /// Mate two genomes with a crossover operator picked from the config, keeping
+    /// the child within its recurrence policy
+    pub fn mate_with_config(
+        &self,
+        other: &Genome,
+        fittest: bool,
+        config: &MutationConfig,
+    ) -> Genome {
+        let parents = if fittest {
+            [self, other]
         } else {
-            other.mate_genes(self)
-        }
+            [other, self]
+        };
+        let mut child = CrossoverOperator::choose(&config.crossover_operators)
+            .unwrap_or(CrossoverOperator::Uniform)
+            .crossover(&parents, config);
+        child.enforce_recurrence_policy(config.effective_recurrence_policy());
+        child
     }
 
-    fn mate_genes(&self, other: &Genome) -> Genome {
-        let mut genome = Genome::default();
-        // NEAT paper: 40% of crossovers use average weights for matching genes
-        let use_average_weights = rand::random::<f64>() < 0.4;
+    /// Child of this genome, the fittest parent, and `others`. Neurons and disjoint
+    /// or excess genes come from this genome, each neuron in several parents is
+    /// taken from a random one of them. `matching` picks the child gene out of the
+    /// matching genes of the parents, this genome's first. Genes disabled in any
+    /// parent stay disabled with probability `disable_rate`. Frozen genes and
+    /// neurons are copied from this genome.
+    pub fn crossover_genes<F>(
+        &self,
+        others: &[&Genome],
+        disable_rate: f64,
+        mut matching: F,
+    ) -> Genome
+    where
+        F: FnMut(&[&Gene]) -> Gene,
+    {
+        let mut rng = rng::thread_rng();
+        let mut genome = Genome {
+            last_neuron_id: self.last_neuron_id,
+            input_neurons: self.input_neurons,
+            output_neurons: self.output_neurons,
+            ..Genome::default()
+        };
+        for node in &self.nodes {
+            if node.frozen() {
+                genome.add_node(*node);
+                continue;
+            }
+            let mut nodes = vec![node];
+            nodes.extend(
+                others
+                    .iter()
+                    .filter_map(|other| other.get_node(node.id()))
+                    .filter(|other_node| other_node.role() == node.role()),
+            );
+            genome.add_node(*nodes[rng.gen_range(0, nodes.len())]);
+        }
 
         for gene in &self.genes {
-            let mut child_gene = match other.genes.binary_search(gene) {
-                Ok(position) => {
-                    // Matching gene in both parents
-                    if use_average_weights {
-                        // Inherit average weight from both parents
-                        let mut avg_gene = *gene;
-                        avg_gene.set_weight((gene.weight() + other.genes[position].weight()) / 2.0);
-                        avg_gene
-                    } else if rand::random::<f64>() > 0.5 {
-                        *gene
-                    } else {
-                        other.genes[position]
-                    }
-                }
-                Err(_) => {
-                    // Disjoint/excess gene: inherit from fitter parent (self)
-                    *gene
-                }
+            if gene.frozen() {
+                genome.add_gene(*gene);
+                continue;
+            }
+            let mut genes = vec![gene];
+            genes.extend(others.iter().filter_map(|other| other.matching_gene(gene)));
+            let mut child_gene = if genes.len() > 1 {
+                matching(&genes)
+            } else {
+                *gene
             };
 
-            // NEAT rule: if gene is disabled in either parent, 25% chance offspring has it disabled
-            // (reduced from 75% to allow more gene reactivation)
-            let other_gene_disabled = other
-                .genes
-                .binary_search(gene)
-                .ok()
-                .map(|pos| !other.genes[pos].enabled())
-                .unwrap_or(false);
-
-            if !gene.enabled() || other_gene_disabled {
-                if rand::random::<f64>() < 0.25 {
+            // NEAT rule: if gene is disabled in any parent, it may stay disabled
+            if genes.iter().any(|gene| !gene.enabled()) {
+                if rng::random::<f64>() < disable_rate {
                     child_gene.set_disabled();
                 } else {
                     child_gene.set_enabled();
@@ -146,15 +308,65 @@ impl Genome {
         }
         genome
     }
+
     /// Get vector of all genes in this genome
     pub fn get_genes(&self) -> &Vec<Gene> {
         &self.genes
     }
 
+    /// Freeze every gene and neuron, so mutations and crossover only grow new
+    /// structure around them
+    pub fn freeze(&mut self) {
+        self.set_frozen(|_| true, true);
+    }
+
+    /// Unfreeze every gene and neuron
+    pub fn unfreeze(&mut self) {
+        self.set_frozen(|_| true, false);
+    }
+
+    /// Freeze the given neurons and the genes connecting two of them
+    pub fn freeze_subgraph(&mut self, neuron_ids: &[usize]) {
+        let neuron_ids: HashSet<usize> = neuron_ids.iter().cloned().collect();
+        self.set_frozen(|neuron_id| neuron_ids.contains(&neuron_id), true);
+    }
+
+    fn set_frozen<F: Fn(usize) -> bool>(&mut self, selected: F, frozen: bool) {
+        for node in self.nodes.iter_mut().filter(|node| selected(node.id())) {
+            node.set_frozen(frozen);
+        }
+        for gene in self
+            .genes
+            .iter_mut()
+            .filter(|gene| selected(gene.in_neuron_id()) && selected(gene.out_neuron_id()))
+        {
+            gene.set_frozen(frozen);
+        }
+    }
+
+    /// Mutable gene connecting `in_neuron_id` to `out_neuron_id`, for custom
+    /// mutation operators
+    pub fn get_gene_mut(&mut self, in_neuron_id: usize, out_neuron_id: usize) -> Option<&mut Gene> {
+        let gene = Gene::new(in_neuron_id, out_neuron_id, 0f64, true);
+        match self.genes.binary_search(&gene) {
+            Ok(pos) => Some(&mut self.genes[pos]),
+            Err(_) => None,
+        }
+    }
+
+    /// Gene of this genome with the same innovation as `gene`, if any
+    fn matching_gene(&self, gene: &Gene) -> Option<&Gene> {
+        self.genes
+            .binary_search(gene)
+            .ok()
+            .map(|pos| &self.genes[pos])
+            .filter(|own_gene| own_gene.is_same_innovation(gene))
+    }
+
     /// only allow connected nodes
     #[deprecated(since = "0.3.0", note = "please use `add_gene` instead")]
     pub fn inject_gene(&mut self, in_neuron_id: usize, out_neuron_id: usize, weight: f64) {
-        let gene = Gene::new(in_neuron_id, out_neuron_id, weight, true, false);
+        let gene = Gene::new(in_neuron_id, out_neuron_id, weight, true);
         self.add_gene(gene);
     }
     /// Number of genes
@@ -166,44 +378,207 @@ impl Genome {
         self.len() == 0
     }
 
+    #[cfg(test)]
     fn mutate_add_connection(&mut self) {
-        self.mutate_add_connection_with_config(&crate::mutation_config::MutationConfig::default());
+        let mut tracker = InnovationTracker::from_genomes(Some(&*self));
+        self.mutate_add_connection_with_config(&MutationConfig::default(), &mut tracker);
     }
 
     fn mutate_add_connection_with_config(
         &mut self,
-        config: &crate::mutation_config::MutationConfig,
+        config: &MutationConfig,
+        tracker: &mut InnovationTracker,
     ) {
-        let mut rng = rand::thread_rng();
-        let neuron_ids_to_connect = {
-            if self.last_neuron_id == 0 {
-                vec![0, 0]
+        let policy = config.effective_recurrence_policy();
+        let neuron_ids_to_connect = if self.has_io_roles() {
+            match self.role_connection_candidate(policy) {
+                Some(neuron_ids) => neuron_ids,
+                None => return,
+            }
+        } else {
+            let mut rng = rng::thread_rng();
+            let own_neuron_ids = self.neuron_ids();
+            let candidate_ids = (0..self.last_neuron_id + 1)
+                .filter(|id| !tracker.is_allocated_neuron(*id) || own_neuron_ids.contains(id))
+                .collect::<Vec<usize>>();
+            if candidate_ids.is_empty() {
+                return;
+            } else if policy.allows_self_loops() {
+                vec![
+                    *rng.choose(&candidate_ids).unwrap(),
+                    *rng.choose(&candidate_ids).unwrap(),
+                ]
+            } else if candidate_ids.len() < 2 {
+                vec![candidate_ids[0], candidate_ids[0]]
             } else {
-                rand::seq::sample_iter(&mut rng, 0..self.last_neuron_id + 1, 2).unwrap()
+                rand::seq::sample_iter(&mut rng, candidate_ids, 2).unwrap()
             }
         };
-        let gene = Gene::new(
+        if !self.allows_connection(neuron_ids_to_connect[0], neuron_ids_to_connect[1], policy) {
+            return;
+        }
+        for neuron_id in &neuron_ids_to_connect {
+            self.add_new_node(*neuron_id, config);
+        }
+        let mut gene = Gene::new(
             neuron_ids_to_connect[0],
             neuron_ids_to_connect[1],
             Gene::generate_weight_in_range(config.weight_init_range),
             true,
-            false,
+        );
+        gene.set_innovation(
+            tracker.connection_innovation(neuron_ids_to_connect[0], neuron_ids_to_connect[1]),
         );
         self.add_gene(gene);
     }
 
+    /// Random (in, out) pair where `in` is not an output neuron and `out` is not an
+    /// input neuron, the same neuron only if the policy allows self-loops
+    fn role_connection_candidate(&self, policy: RecurrencePolicy) -> Option<Vec<usize>> {
+        let mut rng = rng::thread_rng();
+        let sources = self
+            .nodes
+            .iter()
+            .filter(|node| node.role() != NeuronRole::Output)
+            .map(|node| node.id());
+        let in_neuron_id = rand::seq::sample_iter(&mut rng, sources, 1).ok()?[0];
+        let targets = self
+            .nodes
+            .iter()
+            .filter(|node| {
+                node.role() != NeuronRole::Input
+                    && (node.id() != in_neuron_id || policy.allows_self_loops())
+            })
+            .map(|node| node.id());
+        let out_neuron_id = rand::seq::sample_iter(&mut rng, targets, 1).ok()?[0];
+        Some(vec![in_neuron_id, out_neuron_id])
+    }
+
+    /// True if the recurrence policy allows connecting `in_neuron_id -> out_neuron_id`
+    fn allows_connection(
+        &self,
+        in_neuron_id: usize,
+        out_neuron_id: usize,
+        policy: RecurrencePolicy,
+    ) -> bool {
+        if in_neuron_id == out_neuron_id {
+            policy.allows_self_loops()
+        } else {
+            policy.allows_cycles() || !self.creates_cycle(in_neuron_id, out_neuron_id)
+        }
+    }
+
+    /// First gene, in gene order, that breaks the recurrence policy given the genes
+    /// before it
+    fn recurrence_violation(&self, policy: RecurrencePolicy) -> Option<Gene> {
+        let mut checked = Genome::default();
+        for gene in &self.genes {
+            if !checked.allows_connection(gene.in_neuron_id(), gene.out_neuron_id(), policy) {
+                return Some(*gene);
+            }
+            checked.genes.push(*gene);
+        }
+        None
+    }
+
+    /// Check that genes follow the recurrence policy, disabled genes included
+    pub fn validate_recurrence_policy(&self, policy: RecurrencePolicy) -> Result<(), GenomeError> {
+        match self.recurrence_violation(policy) {
+            None => Ok(()),
+            Some(gene) if gene.in_neuron_id() == gene.out_neuron_id() => {
+                Err(GenomeError::SelfLoop {
+                    neuron_id: gene.in_neuron_id(),
+                })
+            }
+            Some(gene) => Err(GenomeError::Cycle {
+                in_neuron_id: gene.in_neuron_id(),
+                out_neuron_id: gene.out_neuron_id(),
+            }),
+        }
+    }
+
+    /// Remove the genes that break the recurrence policy, keeping genes in gene
+    /// order as long as they don't close a forbidden loop
+    pub fn enforce_recurrence_policy(&mut self, policy: RecurrencePolicy) {
+        if policy == RecurrencePolicy::Recurrent {
+            return;
+        }
+        let genes = std::mem::take(&mut self.genes);
+        for gene in genes {
+            if self.allows_connection(gene.in_neuron_id(), gene.out_neuron_id(), policy) {
+                self.genes.push(gene);
+            }
+        }
+    }
+
+    /// True if connecting `in_neuron_id -> out_neuron_id` closes a loop. Disabled
+    /// genes count because they can be enabled again.
+    fn creates_cycle(&self, in_neuron_id: usize, out_neuron_id: usize) -> bool {
+        if in_neuron_id == out_neuron_id {
+            return true;
+        }
+        let mut visited = HashSet::new();
+        let mut pending = vec![out_neuron_id];
+        while let Some(neuron_id) = pending.pop() {
+            if neuron_id == in_neuron_id {
+                return true;
+            }
+            if visited.insert(neuron_id) {
+                pending.extend(
+                    self.genes
+                        .iter()
+                        .filter(|gene| gene.in_neuron_id() == neuron_id)
+                        .map(|gene| gene.out_neuron_id()),
+                );
+            }
+        }
+        false
+    }
+
+    /// Neuron ids ordered so every neuron comes after the neurons connected into it
+    /// by enabled genes, or None if enabled genes form a cycle.
+    pub fn topological_order(&self) -> Option<Vec<usize>> {
+        let mut incoming: HashMap<usize, usize> =
+            self.nodes.iter().map(|node| (node.id(), 0)).collect();
+        for gene in self.genes.iter().filter(|gene| gene.enabled()) {
+            *incoming.get_mut(&gene.out_neuron_id()).unwrap() += 1;
+        }
+        let mut ready: Vec<usize> = self
+            .nodes
+            .iter()
+            .map(|node| node.id())
+            .filter(|id| incoming[id] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(neuron_id) = ready.pop() {
+            order.push(neuron_id);
+            for gene in self
+                .genes
+                .iter()
+                .filter(|gene| gene.enabled() && gene.in_neuron_id() == neuron_id)
+            {
+                let count = incoming.get_mut(&gene.out_neuron_id()).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    ready.push(gene.out_neuron_id());
+                }
+            }
+        }
+        if order.len() == self.nodes.len() {
+            Some(order)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(test)]
     fn mutate_connection_weight(&mut self) {
-        self.mutate_connection_weight_with_config(
-            &crate::mutation_config::MutationConfig::default(),
-        );
+        self.mutate_connection_weight_with_config(&MutationConfig::default());
     }
 
-    fn mutate_connection_weight_with_config(
-        &mut self,
-        config: &crate::mutation_config::MutationConfig,
-    ) {
-        for gene in &mut self.genes {
-            if rand::random::<f64>() < config.weight_perturbation_rate {
+    fn mutate_connection_weight_with_config(&mut self, config: &MutationConfig) {
+        for gene in self.genes.iter_mut().filter(|gene| !gene.frozen()) {
+            if rng::random::<f64>() < config.weight_perturbation_rate {
                 // Perturbation: add small random value
                 let perturbation = Gene::generate_weight_in_range(config.weight_mutate_power);
                 gene.set_weight(gene.weight() + perturbation);
@@ -215,30 +590,232 @@ impl Genome {
     }
 
     fn mutate_toggle_expression(&mut self) {
-        let mut rng = rand::thread_rng();
-        let selected_gene = rand::seq::sample_iter(&mut rng, 0..self.genes.len(), 1).unwrap()[0];
-        <dyn Mutation>::toggle_expression(&mut self.genes[selected_gene]);
+        if let Some(selected_gene) = self.random_unfrozen_gene() {
+            <dyn Mutation>::toggle_expression(&mut self.genes[selected_gene]);
+        }
+    }
+
+    /// Position of a random gene that isn't frozen
+    fn random_unfrozen_gene(&self) -> Option<usize> {
+        let mut rng = rng::thread_rng();
+        let candidates = (0..self.genes.len()).filter(|pos| !self.genes[*pos].frozen());
+        rand::seq::sample_iter(&mut rng, candidates, 1)
+            .ok()
+            .map(|positions| positions[0])
+    }
+
+    /// Position of a random neuron that isn't frozen
+    fn random_unfrozen_node(&self) -> Option<usize> {
+        let mut rng = rng::thread_rng();
+        let candidates = (0..self.nodes.len()).filter(|pos| !self.nodes[*pos].frozen());
+        rand::seq::sample_iter(&mut rng, candidates, 1)
+            .ok()
+            .map(|positions| positions[0])
+    }
+
+    fn mutate_bias_with_config(&mut self, config: &MutationConfig) {
+        for node in self.nodes.iter_mut().filter(|node| !node.frozen()) {
+            if rng::random::<f64>() < config.bias_replace_rate {
+                // Replace with new random bias
+                node.set_bias(NodeGene::generate_bias(config));
+            } else {
+                // Perturbation: add small random value
+                let perturbation = Gene::generate_weight_in_range(config.bias_mutate_power);
+                node.set_bias(node.bias() + perturbation);
+            }
+        }
+    }
+
+    fn mutate_tau(&mut self, config: &MutationConfig) {
+        let selected_node = match self.random_unfrozen_node() {
+            Some(selected_node) => selected_node,
+            None => return,
+        };
+        <dyn Mutation>::perturb_tau(
+            &mut self.nodes[selected_node],
+            config.tau_mutate_power,
+            config.tau_min,
+            config.tau_max,
+        );
     }
 
-    fn mutate_toggle_bias(&mut self) {
-        let mut rng = rand::thread_rng();
-        let selected_gene = rand::seq::sample_iter(&mut rng, 0..self.genes.len(), 1).unwrap()[0];
-        <dyn Mutation>::toggle_bias(&mut self.genes[selected_gene]);
+    fn mutate_activation(&mut self, allowed: &[Activation]) {
+        if let Some(selected_node) = self.random_unfrozen_node() {
+            <dyn Mutation>::change_activation(&mut self.nodes[selected_node], allowed);
+        }
     }
 
+    #[cfg(test)]
     fn mutate_add_neuron(&mut self) {
-        let (gene1, gene2) = {
-            let mut rng = rand::thread_rng();
-            let selected_gene =
-                rand::seq::sample_iter(&mut rng, 0..self.genes.len(), 1).unwrap()[0];
-            let gene = &mut self.genes[selected_gene];
-            self.last_neuron_id += 1;
-            <dyn Mutation>::add_neuron(gene, self.last_neuron_id)
+        let mut tracker = InnovationTracker::from_genomes(Some(&*self));
+        self.mutate_add_neuron_with_config(&MutationConfig::default(), &mut tracker);
+    }
+
+    fn mutate_add_neuron_with_config(
+        &mut self,
+        config: &MutationConfig,
+        tracker: &mut InnovationTracker,
+    ) {
+        let selected_gene = match self.random_unfrozen_gene() {
+            Some(selected_gene) => selected_gene,
+            None => return,
         };
+        let split_gene = self.genes[selected_gene];
+        if split_gene.in_neuron_id() == split_gene.out_neuron_id()
+            && !config.effective_recurrence_policy().allows_cycles()
+        {
+            // Splitting a self-loop would create a cycle through the new neuron
+            return;
+        }
+        let mut new_neuron_id = tracker.split_neuron_id(&self.genes[selected_gene]);
+        if self.has_neuron(new_neuron_id) {
+            // This genome already split the connection in the current generation
+            new_neuron_id = tracker.new_neuron_id();
+        }
+        let (mut gene1, mut gene2) =
+            <dyn Mutation>::add_neuron(&mut self.genes[selected_gene], new_neuron_id);
+        gene1.set_innovation(tracker.connection_innovation(gene1.in_neuron_id(), new_neuron_id));
+        gene2.set_innovation(tracker.connection_innovation(new_neuron_id, gene2.out_neuron_id()));
+        self.add_new_node(new_neuron_id, config);
         self.add_gene(gene1);
         self.add_gene(gene2);
     }
 
+    /// Remove a random gene, and the hidden neurons it leaves unconnected
+    fn mutate_delete_connection(&mut self) {
+        let selected_gene = match self.random_unfrozen_gene() {
+            Some(selected_gene) => selected_gene,
+            None => return,
+        };
+        let gene = self.genes.remove(selected_gene);
+        for neuron_id in &[gene.in_neuron_id(), gene.out_neuron_id()] {
+            let connected = self.genes.iter().any(|gene| {
+                gene.in_neuron_id() == *neuron_id || gene.out_neuron_id() == *neuron_id
+            });
+            if !connected {
+                self.nodes.retain(|node| {
+                    node.id() != *neuron_id || node.role() != NeuronRole::Hidden || node.frozen()
+                });
+            }
+        }
+    }
+
+    /// Remove a random hidden neuron with every gene connected to it
+    fn mutate_delete_neuron(&mut self) {
+        let hidden_ids = self
+            .nodes
+            .iter()
+            .filter(|node| node.role() == NeuronRole::Hidden && !node.frozen())
+            .map(|node| node.id())
+            .filter(|neuron_id| {
+                !self.genes.iter().any(|gene| {
+                    gene.frozen()
+                        && (gene.in_neuron_id() == *neuron_id || gene.out_neuron_id() == *neuron_id)
+                })
+            })
+            .collect::<Vec<usize>>();
+        let mut rng = rng::thread_rng();
+        let neuron_id = match rand::seq::sample_iter(&mut rng, hidden_ids, 1) {
+            Ok(neuron_ids) => neuron_ids[0],
+            Err(_) => return,
+        };
+        self.genes
+            .retain(|gene| gene.in_neuron_id() != neuron_id && gene.out_neuron_id() != neuron_id);
+        self.nodes.retain(|node| node.id() != neuron_id);
+    }
+
+    fn has_neuron(&self, neuron_id: usize) -> bool {
+        self.get_node(neuron_id).is_some()
+    }
+
+    fn neuron_ids(&self) -> HashSet<usize> {
+        self.nodes.iter().map(|node| node.id()).collect()
+    }
+
+    /// Number of input neurons, 0 if the genome wasn't created with roles
+    pub fn input_neurons(&self) -> usize {
+        self.input_neurons
+    }
+
+    /// Number of output neurons, 0 if the genome wasn't created with roles
+    pub fn output_neurons(&self) -> usize {
+        self.output_neurons
+    }
+
+    /// True if the genome knows its input and output neurons. Genomes without
+    /// roles read sensors and outputs by position.
+    pub fn has_io_roles(&self) -> bool {
+        self.input_neurons + self.output_neurons > 0
+    }
+
+    /// Ids of the neurons with the given role, sorted
+    pub fn neuron_ids_with_role(&self, role: NeuronRole) -> Vec<usize> {
+        self.nodes
+            .iter()
+            .filter(|node| node.role() == role)
+            .map(|node| node.id())
+            .collect()
+    }
+
+    /// Get vector of all neurons in this genome, sorted by id
+    pub fn get_nodes(&self) -> &Vec<NodeGene> {
+        &self.nodes
+    }
+
+    /// Get the neuron with the given id
+    pub fn get_node(&self, neuron_id: usize) -> Option<&NodeGene> {
+        self.nodes
+            .binary_search_by_key(&neuron_id, |node| node.id())
+            .ok()
+            .map(|pos| &self.nodes[pos])
+    }
+
+    /// Add a neuron, replacing the neuron with the same id if it exists
+    pub fn add_node(&mut self, node: NodeGene) {
+        if node.id() > self.last_neuron_id {
+            self.last_neuron_id = node.id();
+        }
+        match self
+            .nodes
+            .binary_search_by_key(&node.id(), |node| node.id())
+        {
+            Ok(pos) => self.nodes[pos] = node,
+            Err(pos) => self.nodes.insert(pos, node),
+        }
+    }
+
+    /// Add a hidden neuron created by a structural mutation, with a time constant
+    /// initialized from the config. Existing neurons are left untouched.
+    fn add_new_node(&mut self, neuron_id: usize, config: &MutationConfig) {
+        if !self.has_neuron(neuron_id) {
+            let mut node = NodeGene::new(neuron_id, NeuronRole::Hidden);
+            node.set_tau(NodeGene::generate_tau(config));
+            self.add_node(node);
+        }
+    }
+
+    /// Initialize the time constant and bias of every neuron from the config
+    pub fn init_nodes(&mut self, config: &MutationConfig) {
+        for node in &mut self.nodes {
+            node.set_tau(NodeGene::generate_tau(config));
+            node.set_bias(NodeGene::generate_bias(config));
+        }
+    }
+
+    /// Set the same time constant on every neuron
+    pub fn set_tau(&mut self, tau: f64) {
+        for node in &mut self.nodes {
+            node.set_tau(tau);
+        }
+    }
+
+    fn ensure_node(&mut self, neuron_id: usize) {
+        if !self.has_neuron(neuron_id) {
+            self.add_node(NodeGene::new(neuron_id, NeuronRole::Hidden));
+        }
+    }
+
+    #[cfg(test)]
     fn add_connection(&mut self, in_neuron_id: usize, out_neuron_id: usize) {
         let gene = <dyn Mutation>::add_connection(in_neuron_id, out_neuron_id);
         self.add_gene(gene);
@@ -247,15 +824,28 @@ impl Genome {
     /// Add a new gene and checks if is allowd. Only can connect next neuron or already connected
     /// neurons.
     pub fn add_gene(&mut self, gene: Gene) {
+        if let Err(error) = self.try_add_gene(gene) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Add a new gene, returning an error instead of panicking if it's not allowed.
+    /// Adding an existing gene enables it.
+    pub fn try_add_gene(&mut self, gene: Gene) -> Result<(), GenomeError> {
         let max_neuron_id = self.last_neuron_id + 1;
 
         if gene.in_neuron_id() == gene.out_neuron_id() && gene.in_neuron_id() > max_neuron_id {
-            panic!(
-                "Try to create a gene neuron unconnected, max neuron id {}, {} -> {}",
+            return Err(GenomeError::DanglingNeuron {
                 max_neuron_id,
-                gene.in_neuron_id(),
-                gene.out_neuron_id()
-            );
+                in_neuron_id: gene.in_neuron_id(),
+                out_neuron_id: gene.out_neuron_id(),
+            });
+        }
+        if !gene.weight().is_finite() {
+            return Err(GenomeError::NonFiniteWeight {
+                in_neuron_id: gene.in_neuron_id(),
+                out_neuron_id: gene.out_neuron_id(),
+            });
         }
 
         if gene.in_neuron_id() > self.last_neuron_id {
@@ -264,11 +854,84 @@ impl Genome {
         if gene.out_neuron_id() > self.last_neuron_id {
             self.last_neuron_id = gene.out_neuron_id();
         }
+        self.ensure_node(gene.in_neuron_id());
+        self.ensure_node(gene.out_neuron_id());
         match self.genes.binary_search(&gene) {
-            Ok(pos) => self.genes[pos].set_enabled(),
+            Ok(pos) if !self.genes[pos].frozen() => self.genes[pos].set_enabled(),
+            Ok(_) => {}
             Err(_) => self.genes.push(gene),
         }
         self.genes.sort();
+        Ok(())
+    }
+
+    /// Check the invariants the rest of the crate relies on: genes sorted and unique
+    /// with finite weights, every connected neuron has a valid node gene within
+    /// `last_neuron_id`, and the input/output counts match the neuron roles.
+    pub fn validate(&self) -> Result<(), GenomeError> {
+        for pair in self.genes.windows(2) {
+            match pair[0].cmp(&pair[1]) {
+                cmp::Ordering::Less => {}
+                cmp::Ordering::Equal => {
+                    return Err(GenomeError::DuplicateGene {
+                        in_neuron_id: pair[1].in_neuron_id(),
+                        out_neuron_id: pair[1].out_neuron_id(),
+                    })
+                }
+                cmp::Ordering::Greater => return Err(GenomeError::UnsortedGenes),
+            }
+        }
+        if self
+            .nodes
+            .windows(2)
+            .any(|pair| pair[0].id() >= pair[1].id())
+        {
+            return Err(GenomeError::UnsortedNeurons);
+        }
+        for gene in &self.genes {
+            if !gene.weight().is_finite() {
+                return Err(GenomeError::NonFiniteWeight {
+                    in_neuron_id: gene.in_neuron_id(),
+                    out_neuron_id: gene.out_neuron_id(),
+                });
+            }
+            for neuron_id in &[gene.in_neuron_id(), gene.out_neuron_id()] {
+                if !self.has_neuron(*neuron_id) {
+                    return Err(GenomeError::MissingNeuron {
+                        neuron_id: *neuron_id,
+                    });
+                }
+            }
+        }
+        for node in &self.nodes {
+            if node.id() > self.last_neuron_id {
+                return Err(GenomeError::NeuronIdOutOfRange {
+                    neuron_id: node.id(),
+                    last_neuron_id: self.last_neuron_id,
+                });
+            }
+            if !node.bias().is_finite() || !node.tau().is_finite() || node.tau() <= 0f64 {
+                return Err(GenomeError::InvalidNeuron {
+                    neuron_id: node.id(),
+                });
+            }
+        }
+        if self.has_io_roles() {
+            for (role, expected) in &[
+                (NeuronRole::Input, self.input_neurons),
+                (NeuronRole::Output, self.output_neurons),
+            ] {
+                let found = self.neuron_ids_with_role(*role).len();
+                if found != *expected {
+                    return Err(GenomeError::RoleMismatch {
+                        role: *role,
+                        expected: *expected,
+                        found,
+                    });
+                }
+            }
+        }
+        Ok(())
     }
 
     /// Compare another Genome for species equality
@@ -279,7 +942,13 @@ impl Genome {
 
     /// Compare another Genome for species equality using a custom threshold
     pub fn is_same_specie_with_threshold(&self, other: &Genome, threshold: f64) -> bool {
-        self.compatibility_distance(other) < threshold
+        self.compatibility_distance(other, &MutationConfig::default()) < threshold
+    }
+
+    /// Compare another Genome for species equality with the compatibility
+    /// coefficients and threshold of `config`
+    pub fn is_same_specie_with_config(&self, other: &Genome, config: &MutationConfig) -> bool {
+        self.compatibility_distance(other, config) < config.compatibility_threshold
     }
 
     /// Total weigths of all genes
@@ -297,64 +966,138 @@ impl Genome {
         self.genes.len()
     }
 
-    // http://nn.cs.utexas.edu/downloads/papers/stanley.ec02.pdf - Pag. 110
-    // I have considered disjoint and excess genes as the same
-    fn compatibility_distance(&self, other: &Genome) -> f64 {
-        // TODO: optimize this method
-        let c2 = 1f64;
-        let c3 = 0.2f64;
+    /// Size of the network: connection genes plus hidden neurons
+    pub fn complexity(&self) -> usize {
+        self.genes.len() + self.neuron_ids_with_role(NeuronRole::Hidden).len()
+    }
 
-        // Number of excess
-        let n1 = self.genes.len();
-        let n2 = other.genes.len();
-        let n = cmp::max(n1, n2);
+    /// Functionally equivalent genome without disabled genes nor hidden neurons
+    /// that can't reach an output, with neuron ids renumbered compactly: inputs,
+    /// then outputs, then hidden neurons.
+    ///
+    /// Hidden neurons without a path from the inputs are kept when they reach an
+    /// output, their bias still drives the network. Genomes without roles don't
+    /// know their outputs, they only lose their disabled genes.
+    pub fn simplify(&self) -> Genome {
+        let genes: Vec<Gene> = self
+            .genes
+            .iter()
+            .filter(|gene| gene.enabled())
+            .cloned()
+            .collect();
+        if !self.has_io_roles() {
+            return Genome {
+                genes,
+                ..self.clone()
+            };
+        }
+
+        let mut useful: HashSet<usize> = self
+            .neuron_ids_with_role(NeuronRole::Output)
+            .into_iter()
+            .collect();
+        let mut pending: Vec<usize> = useful.iter().cloned().collect();
+        while let Some(neuron_id) = pending.pop() {
+            for gene in genes
+                .iter()
+                .filter(|gene| gene.out_neuron_id() == neuron_id)
+            {
+                if useful.insert(gene.in_neuron_id()) {
+                    pending.push(gene.in_neuron_id());
+                }
+            }
+        }
 
-        if n == 0 {
-            return 0f64; // no genes in any genome, the genomes are equal
+        let mut ids = HashMap::new();
+        let mut nodes = vec![];
+        for role in &[NeuronRole::Input, NeuronRole::Output, NeuronRole::Hidden] {
+            for node in self.nodes.iter().filter(|node| node.role() == *role) {
+                if *role == NeuronRole::Hidden && !useful.contains(&node.id()) {
+                    continue;
+                }
+                let mut simplified = NodeGene::new(nodes.len(), *role);
+                simplified.set_bias(node.bias());
+                simplified.set_tau(node.tau());
+                simplified.set_activation(node.activation());
+                simplified.set_frozen(node.frozen());
+                ids.insert(node.id(), simplified.id());
+                nodes.push(simplified);
+            }
         }
 
-        let matching_genes = self
-            .genes
+        let mut genes: Vec<Gene> = genes
             .iter()
-            .filter(|i1_gene| other.genes.contains(i1_gene))
-            .collect::<Vec<&Gene>>();
-        let n3 = matching_genes.len();
+            .filter_map(|gene| {
+                match (
+                    ids.get(&gene.in_neuron_id()),
+                    ids.get(&gene.out_neuron_id()),
+                ) {
+                    (Some(in_neuron_id), Some(out_neuron_id)) => {
+                        let mut simplified =
+                            Gene::new(*in_neuron_id, *out_neuron_id, gene.weight(), true);
+                        simplified.set_innovation(gene.innovation());
+                        simplified.set_frozen(gene.frozen());
+                        Some(simplified)
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+        genes.sort();
 
-        // Disjoint / excess genes
-        let d = n1 + n2 - (2 * n3);
+        Genome {
+            genes,
+            last_neuron_id: nodes.len().saturating_sub(1),
+            nodes,
+            input_neurons: self.input_neurons,
+            output_neurons: self.output_neurons,
+        }
+    }
 
-        // average weight differences of matching genes
-        let mut w = matching_genes.iter().fold(0f64, |acc, &m_gene| {
-            acc + (m_gene.weight()
-                - &other.genes[other.genes.binary_search(m_gene).unwrap()].weight())
-                .abs()
-        });
+    /// Genes and neurons that differ from `other`
+    pub fn diff(&self, other: &Genome) -> GenomeDiff {
+        GenomeDiff::new(self, other)
+    }
 
-        // if no matching genes then are completely different
-        w = if n3 == 0 {
-            COMPATIBILITY_THRESHOLD
-        } else {
-            w / n3 as f64
+    // http://nn.cs.utexas.edu/downloads/papers/stanley.ec02.pdf - Pag. 110
+    /// Compatibility distance δ = c1·E/N + c2·D/N + c3·W̄ + node term, with the
+    /// coefficients and normalization threshold of `config`. Excess and disjoint
+    /// genes are counted as in `GenomeDiff`; the node term adds the disjoint neurons
+    /// and the average bias and activation differences of the neurons in both
+    /// genomes.
+    pub fn compatibility_distance(&self, other: &Genome, config: &MutationConfig) -> f64 {
+        let diff = self.diff(other);
+
+        let normalize = |size: usize| {
+            if size > config.compatibility_normalize_threshold {
+                size as f64
+            } else {
+                1f64
+            }
         };
+        let n = normalize(cmp::max(self.genes.len(), other.genes.len()));
+        let n_nodes = normalize(cmp::max(self.nodes.len(), other.nodes.len()));
 
-        // compatibility distance
-        (c2 * d as f64 / n as f64) + c3 * w
+        config.compatibility_excess_coefficient * diff.excess.len() as f64 / n
+            + config.compatibility_disjoint_coefficient * diff.disjoint.len() as f64 / n
+            + config.compatibility_weight_coefficient * diff.average_weight_difference()
+            + config.compatibility_neuron_difference_coefficient * diff.average_neuron_difference()
+            + config.compatibility_node_coefficient * diff.disjoint_neurons() as f64 / n_nodes
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::f64::EPSILON;
 
     #[test]
     fn mutation_connection_weight() {
         let mut genome = Genome::default();
-        genome.add_gene(Gene::new(0, 0, 1f64, true, false));
+        genome.add_gene(Gene::new(0, 0, 1f64, true));
         let orig_gene = genome.genes[0];
         genome.mutate_connection_weight();
         // These should not be same size
-        assert!((genome.genes[0].weight() - orig_gene.weight()).abs() > EPSILON);
+        assert!((genome.genes[0].weight() - orig_gene.weight()).abs() > f64::EPSILON);
     }
 
     #[test]
@@ -382,71 +1125,136 @@ mod tests {
     #[should_panic(expected = "Try to create a gene neuron unconnected, max neuron id 1, 2 -> 2")]
     fn try_to_inject_a_unconnected_neuron_gene_should_panic() {
         let mut genome1 = Genome::default();
-        genome1.add_gene(Gene::new(2, 2, 0.5f64, true, false));
+        genome1.add_gene(Gene::new(2, 2, 0.5f64, true));
+    }
+
+    #[test]
+    fn try_add_gene_should_return_errors_instead_of_panicking() {
+        let mut genome = Genome::default();
+        assert_eq!(
+            genome.try_add_gene(Gene::new(2, 2, 0.5f64, true)),
+            Err(GenomeError::DanglingNeuron {
+                max_neuron_id: 1,
+                in_neuron_id: 2,
+                out_neuron_id: 2,
+            })
+        );
+        assert_eq!(
+            genome.try_add_gene(Gene::new(0, 1, f64::NAN, true)),
+            Err(GenomeError::NonFiniteWeight {
+                in_neuron_id: 0,
+                out_neuron_id: 1,
+            })
+        );
+        assert!(genome.try_add_gene(Gene::new(0, 1, 0.5f64, true)).is_ok());
+        assert!(genome.validate().is_ok());
+    }
+
+    #[test]
+    fn try_from_genes_should_reject_invalid_genes() {
+        let gene = Gene::new(0, 1, 1f64, true);
+        assert!(Genome::try_from_genes(vec![gene], 1).is_ok());
+        assert_eq!(
+            Genome::try_from_genes(vec![gene, gene], 1).unwrap_err(),
+            GenomeError::DuplicateGene {
+                in_neuron_id: 0,
+                out_neuron_id: 1,
+            }
+        );
+        assert_eq!(
+            Genome::try_from_genes(vec![Gene::new(0, 4, 1f64, true)], 1).unwrap_err(),
+            GenomeError::NeuronIdOutOfRange {
+                neuron_id: 4,
+                last_neuron_id: 1,
+            }
+        );
+        assert!(Genome::try_from_genes(vec![Gene::new(0, 1, f64::INFINITY, true)], 1).is_err());
+    }
+
+    #[test]
+    fn validate_should_check_neurons() {
+        let mut genome = Genome::new_initialized(2, 1);
+        assert!(genome.validate().is_ok());
+        let mut node = *genome.get_node(2).unwrap();
+        node.set_tau(0f64);
+        genome.add_node(node);
+        assert_eq!(
+            genome.validate(),
+            Err(GenomeError::InvalidNeuron { neuron_id: 2 })
+        );
+        genome.add_node(NodeGene::new(2, NeuronRole::Hidden));
+        assert_eq!(
+            genome.validate(),
+            Err(GenomeError::RoleMismatch {
+                role: NeuronRole::Output,
+                expected: 1,
+                found: 0,
+            })
+        );
     }
 
     #[test]
     fn two_genomes_without_differences_should_be_in_same_specie() {
         let mut genome1 = Genome::default();
-        genome1.add_gene(Gene::new(0, 0, 1f64, true, false));
-        genome1.add_gene(Gene::new(0, 1, 1f64, true, false));
+        genome1.add_gene(Gene::new(0, 0, 1f64, true));
+        genome1.add_gene(Gene::new(0, 1, 1f64, true));
         let mut genome2 = Genome::default();
-        genome2.add_gene(Gene::new(0, 0, 0f64, true, false));
-        genome2.add_gene(Gene::new(0, 1, 0f64, true, false));
-        genome2.add_gene(Gene::new(0, 2, 0f64, true, false));
+        genome2.add_gene(Gene::new(0, 0, 0f64, true));
+        genome2.add_gene(Gene::new(0, 1, 0f64, true));
+        genome2.add_gene(Gene::new(0, 2, 0f64, true));
         assert!(genome1.is_same_specie(&genome2));
     }
 
     #[test]
     fn two_genomes_with_enought_difference_should_be_in_different_species() {
         let mut genome1 = Genome::default();
-        genome1.add_gene(Gene::new(0, 0, 1f64, true, false));
-        genome1.add_gene(Gene::new(0, 1, 1f64, true, false));
+        genome1.add_gene(Gene::new(0, 0, 1f64, true));
+        genome1.add_gene(Gene::new(0, 1, 1f64, true));
         let mut genome2 = Genome::default();
-        genome2.add_gene(Gene::new(0, 0, 20f64, true, false));
-        genome2.add_gene(Gene::new(0, 1, 20f64, true, false));
-        genome2.add_gene(Gene::new(0, 2, 1f64, true, false));
-        genome2.add_gene(Gene::new(0, 3, 1f64, true, false));
+        genome2.add_gene(Gene::new(0, 0, 20f64, true));
+        genome2.add_gene(Gene::new(0, 1, 20f64, true));
+        genome2.add_gene(Gene::new(0, 2, 1f64, true));
+        genome2.add_gene(Gene::new(0, 3, 1f64, true));
         assert!(!genome1.is_same_specie(&genome2));
     }
 
     #[test]
     fn already_existing_gene_should_be_not_duplicated() {
         let mut genome1 = Genome::default();
-        genome1.add_gene(Gene::new(0, 0, 1f64, true, false));
+        genome1.add_gene(Gene::new(0, 0, 1f64, true));
         genome1.add_connection(0, 0);
         assert_eq!(genome1.genes.len(), 1);
-        assert!((genome1.get_genes()[0].weight() - 1f64).abs() < EPSILON);
+        assert!((genome1.get_genes()[0].weight() - 1f64).abs() < f64::EPSILON);
     }
 
     #[test]
     fn adding_an_existing_gene_disabled_should_enable_original() {
         let mut genome1 = Genome::default();
-        genome1.add_gene(Gene::new(0, 1, 0f64, true, false));
+        genome1.add_gene(Gene::new(0, 1, 0f64, true));
         genome1.mutate_add_neuron();
         assert!(!genome1.genes[0].enabled());
         assert!(genome1.genes.len() == 3);
         genome1.add_connection(0, 1);
         assert!(genome1.genes[0].enabled());
-        assert!((genome1.genes[0].weight() - 0f64).abs() < EPSILON);
+        assert!((genome1.genes[0].weight() - 0f64).abs() < f64::EPSILON);
         assert_eq!(genome1.genes.len(), 3);
     }
 
     #[test]
     fn genomes_with_same_genes_with_little_differences_on_weight_should_be_in_same_specie() {
         let mut genome1 = Genome::default();
-        genome1.add_gene(Gene::new(0, 0, 16f64, true, false));
+        genome1.add_gene(Gene::new(0, 0, 16f64, true));
         let mut genome2 = Genome::default();
-        genome2.add_gene(Gene::new(0, 0, 16.1f64, true, false));
+        genome2.add_gene(Gene::new(0, 0, 16.1f64, true));
         assert!(genome1.is_same_specie(&genome2));
     }
 
     #[test]
     fn genomes_with_same_genes_with_big_differences_on_weight_should_be_in_other_specie() {
         let mut genome1 = Genome::default();
-        genome1.add_gene(Gene::new(0, 0, 5f64, true, false));
+        genome1.add_gene(Gene::new(0, 0, 5f64, true));
         let mut genome2 = Genome::default();
-        genome2.add_gene(Gene::new(0, 0, 25f64, true, false));
+        genome2.add_gene(Gene::new(0, 0, 25f64, true));
         assert!(!genome1.is_same_specie(&genome2));
     }
 
@@ -468,14 +1276,420 @@ mod tests {
         assert_eq!(genome1.genes[5].out_neuron_id(), 4);
     }
 
+    #[test]
+    fn genomes_splitting_same_connection_should_share_neuron_id() {
+        let mut genome1 = Genome::new_initialized(1, 1);
+        let mut genome2 = genome1.clone();
+        let mut tracker = InnovationTracker::from_genomes(Some(&genome1));
+        let config = MutationConfig::default();
+        genome1.mutate_add_neuron_with_config(&config, &mut tracker);
+        genome2.mutate_add_neuron_with_config(&config, &mut tracker);
+        assert_eq!(genome1.len(), 3);
+        for (gene1, gene2) in genome1.genes.iter().zip(genome2.genes.iter()) {
+            assert!(gene1.is_same_innovation(gene2));
+        }
+        assert!(
+            genome1
+                .compatibility_distance(&genome2, &MutationConfig::default())
+                .abs()
+                < f64::EPSILON
+        );
+        assert_eq!(
+            genome1.genes.len(),
+            genome1.mate(&genome2, true).genes.len()
+        );
+    }
+
+    #[test]
+    fn genes_with_different_innovation_should_not_match() {
+        let mut gene1 = Gene::new(0, 1, 1f64, true);
+        gene1.set_innovation(1);
+        let mut gene2 = gene1;
+        gene2.set_innovation(2);
+        let mut genome1 = Genome::default();
+        genome1.add_gene(gene1);
+        let mut genome2 = Genome::default();
+        genome2.add_gene(gene2);
+        assert!(genome1.matching_gene(&gene2).is_none());
+        assert!(genome2.matching_gene(&gene2).is_some());
+    }
+
+    #[test]
+    fn genomes_initialized_has_input_and_output_nodes() {
+        let genome = Genome::new_unconnected(2, 1);
+        assert_eq!(genome.get_nodes().len(), 3);
+        assert_eq!(genome.get_node(1).unwrap().role(), NeuronRole::Input);
+        assert_eq!(genome.get_node(2).unwrap().role(), NeuronRole::Output);
+        assert_eq!(genome.len(), 3);
+    }
+
+    #[test]
+    fn bias_mutation_should_change_node_biases() {
+        let config = MutationConfig::new().bias_replace_rate(0f64).build();
+        let mut genome = Genome::new_initialized(2, 1);
+        genome.mutate_bias_with_config(&config);
+        assert!(genome.get_nodes().iter().any(|node| node.bias() != 0f64));
+        for node in genome.get_nodes() {
+            assert!(node.bias().abs() <= config.bias_mutate_power);
+        }
+    }
+
+    #[test]
+    fn initial_biases_should_be_zero_unless_configured() {
+        let mut genome = Genome::new_initialized(2, 1);
+        genome.init_nodes(&MutationConfig::default());
+        assert!(genome.get_nodes().iter().all(|node| node.bias() == 0f64));
+        let config = MutationConfig::new().bias_init_range(1f64).build();
+        genome.init_nodes(&config);
+        assert!(genome.get_nodes().iter().any(|node| node.bias() != 0f64));
+        for node in genome.get_nodes() {
+            assert!(node.bias().abs() <= 1f64);
+        }
+    }
+
+    #[test]
+    fn bias_differences_should_increase_compatibility_distance() {
+        let genome1 = Genome::new_initialized(2, 1);
+        let mut genome2 = genome1.clone();
+        let mut node = *genome2.get_node(0).unwrap();
+        node.set_bias(3f64);
+        genome2.add_node(node);
+        assert!(
+            genome1
+                .compatibility_distance(&genome1, &MutationConfig::default())
+                .abs()
+                < f64::EPSILON
+        );
+        assert!(genome1.compatibility_distance(&genome2, &MutationConfig::default()) > 0f64);
+    }
+
+    #[test]
+    fn neuron_differences_should_have_their_own_coefficient() {
+        let genome1 = Genome::new_initialized(2, 1);
+        let mut genome2 = genome1.clone();
+        let mut node = *genome2.get_node(0).unwrap();
+        node.set_bias(3f64);
+        genome2.add_node(node);
+        let distance = |config: &MutationConfig| genome1.compatibility_distance(&genome2, config);
+        assert!((distance(&MutationConfig::default()) - 0.2).abs() < 1e-12);
+        let weights_only = MutationConfig::new()
+            .compatibility_coefficients(1f64, 1f64, 5f64)
+            .compatibility_neuron_difference_coefficient(0f64)
+            .build();
+        assert!(distance(&weights_only).abs() < f64::EPSILON);
+        let neurons_only = MutationConfig::new()
+            .compatibility_coefficients(1f64, 1f64, 0f64)
+            .compatibility_neuron_difference_coefficient(1f64)
+            .build();
+        assert!((distance(&neurons_only) - 1f64).abs() < 1e-12);
+    }
+
+    #[test]
+    fn compatibility_distance_should_split_excess_and_disjoint_genes() {
+        let mut genome1 = Genome::default();
+        let mut genome2 = Genome::default();
+        for (in_neuron_id, out_neuron_id, innovation) in &[(0, 1, 1), (0, 2, 2), (1, 2, 4)] {
+            let mut gene = Gene::new(*in_neuron_id, *out_neuron_id, 0f64, true);
+            gene.set_innovation(*innovation);
+            genome1.add_gene(gene);
+        }
+        for (in_neuron_id, out_neuron_id, innovation) in &[(0, 1, 1), (1, 0, 3)] {
+            let mut gene = Gene::new(*in_neuron_id, *out_neuron_id, 0f64, true);
+            gene.set_innovation(*innovation);
+            genome2.add_gene(gene);
+        }
+        let diff = genome1.diff(&genome2);
+        assert_eq!(diff.matching.len(), 1);
+        assert_eq!(diff.disjoint.len(), 2);
+        assert_eq!(diff.excess.len(), 1);
+
+        let excess_only = MutationConfig::new()
+            .compatibility_coefficients(1f64, 0f64, 0f64)
+            .build();
+        let distance = genome1.compatibility_distance(&genome2, &excess_only);
+        assert!((distance - 1f64 / 3f64).abs() < f64::EPSILON);
+
+        let unnormalized = MutationConfig::new()
+            .compatibility_coefficients(1f64, 0f64, 0f64)
+            .compatibility_normalize_threshold(20)
+            .build();
+        let distance = genome1.compatibility_distance(&genome2, &unnormalized);
+        assert!((distance - 1f64).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn compatibility_distance_should_count_disjoint_neurons() {
+        let genome1 = Genome::new_initialized(1, 1);
+        let mut genome2 = genome1.clone();
+        genome2.add_node(NodeGene::new(2, NeuronRole::Hidden));
+        let config = MutationConfig::new()
+            .compatibility_node_coefficient(3f64)
+            .build();
+        assert!(
+            genome1
+                .compatibility_distance(&genome2, &MutationConfig::default())
+                .abs()
+                < f64::EPSILON
+        );
+        assert!((genome1.compatibility_distance(&genome2, &config) - 1f64).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn simplify_should_drop_disabled_genes_and_dead_ends() {
+        let mut genome = Genome::new_initialized(2, 1);
+        genome.add_node(NodeGene::new(7, NeuronRole::Hidden));
+        genome.add_node(NodeGene::new(9, NeuronRole::Hidden));
+        genome.add_gene(Gene::new(0, 7, 1f64, true));
+        genome.add_gene(Gene::new(7, 2, 1f64, true));
+        genome.add_gene(Gene::new(1, 9, 1f64, true));
+        genome
+            .genes
+            .iter_mut()
+            .find(|gene| gene.in_neuron_id() == 1 && gene.out_neuron_id() == 2)
+            .unwrap()
+            .set_disabled();
+
+        let simplified = genome.simplify();
+        assert_eq!(simplified.validate(), Ok(()));
+        assert_eq!(simplified.len(), 4);
+        assert_eq!(simplified.neuron_ids_with_role(NeuronRole::Hidden), vec![3]);
+        let connections: Vec<(usize, usize)> = simplified
+            .get_genes()
+            .iter()
+            .map(|gene| (gene.in_neuron_id(), gene.out_neuron_id()))
+            .collect();
+        assert_eq!(connections, vec![(0, 2), (0, 3), (3, 2)]);
+    }
+
+    #[test]
+    fn crossover_should_inherit_nodes_of_fittest_parent() {
+        let mut genome1 = Genome::new_initialized(1, 1);
+        let mut hidden = NodeGene::new(5, NeuronRole::Hidden);
+        hidden.set_bias(2f64);
+        genome1.add_node(hidden);
+        let genome2 = Genome::new_initialized(1, 1);
+        let child = genome1.mate(&genome2, true);
+        assert_eq!(child.get_nodes().len(), 3);
+        assert_eq!(child.get_node(5), Some(&hidden));
+        assert_eq!(genome2.mate(&genome1, true).get_nodes().len(), 2);
+    }
+
+    #[test]
+    fn mutate_activation_should_only_use_allowed_activations() {
+        let mut genome = Genome::new_initialized(2, 1);
+        for _ in 0..20 {
+            genome.mutate_activation(&[Activation::Tanh, Activation::Gaussian]);
+        }
+        for node in genome.get_nodes() {
+            assert!(
+                [Activation::Sigmoid, Activation::Tanh, Activation::Gaussian]
+                    .contains(&node.activation())
+            );
+        }
+        assert!(genome
+            .get_nodes()
+            .iter()
+            .any(|node| node.activation() != Activation::Sigmoid));
+    }
+
+    #[test]
+    fn mutate_delete_connection_should_drop_orphan_hidden_neurons() {
+        let mut genome = Genome::new_initialized(1, 1);
+        genome.mutate_add_neuron();
+        assert_eq!(genome.complexity(), 4);
+        let hidden_id = genome.neuron_ids_with_role(NeuronRole::Hidden)[0];
+        genome
+            .genes
+            .retain(|gene| gene.out_neuron_id() == hidden_id);
+        genome.mutate_delete_connection();
+        assert!(genome.neuron_ids_with_role(NeuronRole::Hidden).is_empty());
+        assert_eq!(genome.get_nodes().len(), 2);
+        assert_eq!(genome.validate(), Ok(()));
+    }
+
+    #[test]
+    fn mutate_delete_neuron_should_keep_input_and_output_neurons() {
+        let mut genome = Genome::new_initialized(2, 1);
+        genome.mutate_delete_neuron();
+        assert_eq!(genome.total_genes(), 2);
+        genome.mutate_add_neuron();
+        genome.mutate_delete_neuron();
+        assert_eq!(genome.complexity(), 2);
+        assert_eq!(genome.get_nodes().len(), 3);
+        assert_eq!(genome.validate(), Ok(()));
+    }
+
+    #[test]
+    fn mutate_add_connection_should_respect_neuron_roles() {
+        let mut genome = Genome::new_unconnected(2, 2);
+        for _ in 0..50 {
+            genome.mutate_add_connection();
+        }
+        assert!(genome.total_genes() > 0);
+        for gene in genome.get_genes() {
+            assert_ne!(
+                genome.get_node(gene.in_neuron_id()).unwrap().role(),
+                NeuronRole::Output
+            );
+            assert_ne!(
+                genome.get_node(gene.out_neuron_id()).unwrap().role(),
+                NeuronRole::Input
+            );
+        }
+        assert_eq!(genome.input_neurons(), 2);
+        assert_eq!(genome.output_neurons(), 2);
+    }
+
+    #[test]
+    fn feed_forward_mutations_should_keep_genome_acyclic() {
+        let config = MutationConfig::new().feed_forward(true).build();
+        let mut genome = Genome::new_unconnected(2, 1);
+        let mut tracker = InnovationTracker::from_genomes(Some(&genome));
+        for _ in 0..100 {
+            genome.mutate_add_connection_with_config(&config, &mut tracker);
+            genome.mutate_add_neuron_with_config(&config, &mut tracker);
+        }
+        assert!(genome.topological_order().is_some());
+    }
+
+    #[test]
+    fn structural_mutations_should_follow_recurrence_policy() {
+        rng::EvolutionRng::seed(11);
+        for policy in &[
+            RecurrencePolicy::Recurrent,
+            RecurrencePolicy::NoSelfLoops,
+            RecurrencePolicy::SelfLoopsOnly,
+            RecurrencePolicy::Acyclic,
+        ] {
+            let config = MutationConfig::new().recurrence_policy(*policy).build();
+            for mut genome in [Genome::default(), Genome::new_unconnected(2, 1)] {
+                let mut tracker = InnovationTracker::from_genomes(Some(&genome));
+                for _ in 0..100 {
+                    genome.mutate_add_connection_with_config(&config, &mut tracker);
+                    genome.mutate_add_neuron_with_config(&config, &mut tracker);
+                }
+                assert_eq!(genome.validate_recurrence_policy(*policy), Ok(()));
+                let self_loops = genome
+                    .get_genes()
+                    .iter()
+                    .any(|gene| gene.in_neuron_id() == gene.out_neuron_id());
+                assert_eq!(self_loops, policy.allows_self_loops(), "{:?}", policy);
+            }
+        }
+    }
+
+    #[test]
+    fn validate_recurrence_policy_should_report_loops() {
+        let mut genome = Genome::default();
+        genome.add_gene(Gene::new(0, 1, 1f64, true));
+        genome.add_gene(Gene::new(1, 0, 1f64, false));
+        genome.add_gene(Gene::new(1, 1, 1f64, true));
+        assert_eq!(
+            genome.validate_recurrence_policy(RecurrencePolicy::Recurrent),
+            Ok(())
+        );
+        assert_eq!(
+            genome.validate_recurrence_policy(RecurrencePolicy::NoSelfLoops),
+            Err(GenomeError::SelfLoop { neuron_id: 1 })
+        );
+        assert_eq!(
+            genome.validate_recurrence_policy(RecurrencePolicy::SelfLoopsOnly),
+            Err(GenomeError::Cycle {
+                in_neuron_id: 1,
+                out_neuron_id: 0
+            })
+        );
+
+        genome.enforce_recurrence_policy(RecurrencePolicy::SelfLoopsOnly);
+        assert_eq!(genome.total_genes(), 2);
+        assert_eq!(
+            genome.validate_recurrence_policy(RecurrencePolicy::SelfLoopsOnly),
+            Ok(())
+        );
+        genome.enforce_recurrence_policy(RecurrencePolicy::Acyclic);
+        assert_eq!(genome.total_genes(), 1);
+    }
+
+    #[test]
+    fn frozen_genes_and_neurons_should_survive_mutations() {
+        let mut genome = Genome::new_initialized(2, 1);
+        genome.mutate_add_neuron();
+        genome.freeze();
+        let frozen = genome.clone();
+        let config = MutationConfig::new()
+            .weight_mutation_rate(1f64)
+            .toggle_expression_rate(1f64)
+            .delete_connection_rate(1f64)
+            .delete_neuron_rate(1f64)
+            .bias_mutation_rate(1f64)
+            .activation_mutation_rate(1f64)
+            .allowed_activations(vec![Activation::Tanh])
+            .tau_mutation_rate(1f64)
+            .build();
+        let mut tracker = InnovationTracker::from_genomes(Some(&genome));
+        for _ in 0..20 {
+            genome.mutate_with_tracker(&config, &mut tracker);
+        }
+        for gene in frozen.get_genes() {
+            let mutated = genome.matching_gene(gene).unwrap();
+            assert!((mutated.weight() - gene.weight()).abs() < f64::EPSILON);
+            assert_eq!(mutated.enabled(), gene.enabled());
+        }
+        for node in frozen.get_nodes() {
+            assert_eq!(genome.get_node(node.id()), Some(node));
+        }
+    }
+
+    #[test]
+    fn crossover_should_copy_frozen_subgraph_from_fittest_parent() {
+        let mut genome1 = Genome::new_initialized(2, 1);
+        let mut genome2 = genome1.clone();
+        genome1.get_gene_mut(0, 2).unwrap().set_weight(1f64);
+        genome2.get_gene_mut(0, 2).unwrap().set_weight(-1f64);
+        genome1.freeze_subgraph(&[0, 2]);
+        assert!(genome1.get_gene_mut(0, 2).unwrap().frozen());
+        assert!(!genome1.get_gene_mut(1, 2).unwrap().frozen());
+        assert!(!genome1.get_node(1).unwrap().frozen());
+        for _ in 0..20 {
+            let mut child = genome1.mate(&genome2, true);
+            assert!((child.get_gene_mut(0, 2).unwrap().weight() - 1f64).abs() < f64::EPSILON);
+        }
+        genome1.unfreeze();
+        assert!(!genome1.get_gene_mut(0, 2).unwrap().frozen());
+    }
+
+    #[test]
+    fn topological_order_should_detect_cycles() {
+        let mut genome = Genome::new_unconnected(1, 1);
+        genome.add_gene(Gene::new(0, 1, 1f64, true));
+        genome.add_gene(Gene::new(1, 2, 1f64, true));
+        assert_eq!(genome.topological_order(), Some(vec![0, 1, 2]));
+        assert!(genome.creates_cycle(2, 1));
+        genome.add_gene(Gene::new(2, 1, 1f64, true));
+        assert_eq!(genome.topological_order(), None);
+    }
+
+    #[test]
+    fn neurons_should_get_tau_within_configured_range() {
+        let config = MutationConfig::new().tau(0.5).tau_init_range(0.1).build();
+        let mut genome = Genome::new_initialized(2, 1);
+        genome.init_nodes(&config);
+        let mut tracker = InnovationTracker::from_genomes(Some(&genome));
+        genome.mutate_add_neuron_with_config(&config, &mut tracker);
+        assert_eq!(genome.get_nodes().len(), 4);
+        for node in genome.get_nodes() {
+            assert!(node.tau() >= 0.4 && node.tau() <= 0.6, "{:?}", node);
+        }
+    }
+
     #[test]
     fn crossover_disabled_gene_should_stay_disabled_25_percent() {
         // Parent 1 has disabled gene, parent 2 has enabled gene
         let mut genome1 = Genome::default();
-        genome1.add_gene(Gene::new(0, 1, 1.0, false, false)); // disabled
+        genome1.add_gene(Gene::new(0, 1, 1.0, false)); // disabled
 
         let mut genome2 = Genome::default();
-        genome2.add_gene(Gene::new(0, 1, 1.0, true, false)); // enabled
+        genome2.add_gene(Gene::new(0, 1, 1.0, true)); // enabled
 
         // Run many trials to check probability
         let trials = 1000;
@@ -496,4 +1710,32 @@ mod tests {
             disabled_ratio * 100.0
         );
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn genome_should_round_trip_through_serde() {
+        let mut genome = Genome::new_initialized(2, 1);
+        genome.add_node(NodeGene::new(3, NeuronRole::Hidden));
+        genome.add_gene(Gene::new(0, 3, 0.5f64, true));
+        genome.freeze();
+        let json = serde_json::to_string(&genome).unwrap();
+        let loaded: Genome = serde_json::from_str(&json).unwrap();
+        assert!(genome.diff(&loaded).is_empty());
+        assert_eq!(loaded.input_neurons(), 2);
+        assert!(loaded.get_genes().iter().all(Gene::frozen));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_should_reject_invalid_genomes() {
+        let genome = Genome::new_initialized(2, 1);
+        let mut json = serde_json::to_value(&genome).unwrap();
+        json["nodes"].as_array_mut().unwrap().pop();
+        let error = serde_json::from_value::<Genome>(json).unwrap_err();
+        assert!(
+            error.to_string().contains("Neuron 2 has no node gene"),
+            "{}",
+            error
+        );
+    }
 }
@@ -0,0 +1,348 @@
+use crate::gene::Gene;
+use crate::genome::Genome;
+use crate::node_gene::NodeGene;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Genome of a diff a gene comes from
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DiffSide {
+    /// The genome the diff is computed from
+    Left,
+    /// The genome compared against
+    Right,
+}
+
+/// Genes with the same connection and innovation in both genomes
+#[derive(Debug, Copy, Clone)]
+pub struct MatchingGenes {
+    /// Gene of the left genome
+    pub left: Gene,
+    /// Gene of the right genome
+    pub right: Gene,
+}
+
+impl MatchingGenes {
+    /// Weight of the right gene minus weight of the left gene
+    pub fn weight_delta(&self) -> f64 {
+        self.right.weight() - self.left.weight()
+    }
+
+    /// True if the gene is enabled in one genome and disabled in the other
+    pub fn enabled_changed(&self) -> bool {
+        self.left.enabled() != self.right.enabled()
+    }
+}
+
+/// Gene found in only one of the genomes
+#[derive(Debug, Copy, Clone)]
+pub struct UnmatchedGene {
+    /// Genome that has the gene
+    pub side: DiffSide,
+    /// The gene
+    pub gene: Gene,
+}
+
+/// Structural and parametric differences between two genomes.
+///
+/// Genes are excess when their innovation number is above every innovation of
+/// the other genome, or, without innovation numbers, when they sort after every
+/// gene of the other genome. The remaining unmatched genes are disjoint.
+#[derive(Debug, Clone, Default)]
+pub struct GenomeDiff {
+    /// Genes in both genomes
+    pub matching: Vec<MatchingGenes>,
+    /// Unmatched genes within the innovation range of the other genome
+    pub disjoint: Vec<UnmatchedGene>,
+    /// Unmatched genes beyond the innovation range of the other genome
+    pub excess: Vec<UnmatchedGene>,
+    /// Neurons with the same id in both genomes, left then right
+    pub matching_neurons: Vec<(NodeGene, NodeGene)>,
+    /// Neurons only in the right genome
+    pub added_neurons: Vec<NodeGene>,
+    /// Neurons only in the left genome
+    pub removed_neurons: Vec<NodeGene>,
+}
+
+impl GenomeDiff {
+    /// Compare two genomes with a linear merge over their sorted genes and neurons
+    pub fn new(left: &Genome, right: &Genome) -> GenomeDiff {
+        let mut diff = GenomeDiff::default();
+        diff.merge_genes(left.get_genes(), right.get_genes());
+        diff.merge_nodes(left.get_nodes(), right.get_nodes());
+        diff
+    }
+
+    fn merge_genes(&mut self, left: &[Gene], right: &[Gene]) {
+        let max_innovation = |genes: &[Gene]| genes.iter().map(Gene::innovation).max();
+        let max_left = max_innovation(left).unwrap_or(0);
+        let max_right = max_innovation(right).unwrap_or(0);
+
+        let (mut l, mut r) = (0, 0);
+        while l < left.len() || r < right.len() {
+            match (left.get(l), right.get(r)) {
+                (Some(left_gene), Some(right_gene)) => match left_gene.cmp(right_gene) {
+                    Ordering::Less => {
+                        self.add_unmatched(DiffSide::Left, *left_gene, max_right, false);
+                        l += 1;
+                    }
+                    Ordering::Greater => {
+                        self.add_unmatched(DiffSide::Right, *right_gene, max_left, false);
+                        r += 1;
+                    }
+                    Ordering::Equal => {
+                        if left_gene.is_same_innovation(right_gene) {
+                            self.matching.push(MatchingGenes {
+                                left: *left_gene,
+                                right: *right_gene,
+                            });
+                        } else {
+                            self.add_unmatched(DiffSide::Left, *left_gene, max_right, false);
+                            self.add_unmatched(DiffSide::Right, *right_gene, max_left, false);
+                        }
+                        l += 1;
+                        r += 1;
+                    }
+                },
+                (Some(left_gene), None) => {
+                    self.add_unmatched(DiffSide::Left, *left_gene, max_right, true);
+                    l += 1;
+                }
+                (None, Some(right_gene)) => {
+                    self.add_unmatched(DiffSide::Right, *right_gene, max_left, true);
+                    r += 1;
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+
+    fn add_unmatched(
+        &mut self,
+        side: DiffSide,
+        gene: Gene,
+        other_max_innovation: usize,
+        tail: bool,
+    ) {
+        let excess = if gene.innovation() > 0 && other_max_innovation > 0 {
+            gene.innovation() > other_max_innovation
+        } else {
+            tail
+        };
+        let unmatched = UnmatchedGene { side, gene };
+        if excess {
+            self.excess.push(unmatched);
+        } else {
+            self.disjoint.push(unmatched);
+        }
+    }
+
+    fn merge_nodes(&mut self, left: &[NodeGene], right: &[NodeGene]) {
+        let (mut l, mut r) = (0, 0);
+        while l < left.len() || r < right.len() {
+            match (left.get(l), right.get(r)) {
+                (Some(left_node), Some(right_node)) => match left_node.id().cmp(&right_node.id()) {
+                    Ordering::Less => {
+                        self.removed_neurons.push(*left_node);
+                        l += 1;
+                    }
+                    Ordering::Greater => {
+                        self.added_neurons.push(*right_node);
+                        r += 1;
+                    }
+                    Ordering::Equal => {
+                        self.matching_neurons.push((*left_node, *right_node));
+                        l += 1;
+                        r += 1;
+                    }
+                },
+                (Some(left_node), None) => {
+                    self.removed_neurons.push(*left_node);
+                    l += 1;
+                }
+                (None, Some(right_node)) => {
+                    self.added_neurons.push(*right_node);
+                    r += 1;
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+
+    /// True if both genomes have the same genes and neurons with the same values
+    pub fn is_empty(&self) -> bool {
+        self.disjoint.is_empty()
+            && self.excess.is_empty()
+            && self.added_neurons.is_empty()
+            && self.removed_neurons.is_empty()
+            && self
+                .matching
+                .iter()
+                .all(|genes| genes.weight_delta().abs() < f64::EPSILON && !genes.enabled_changed())
+            && self
+                .matching_neurons
+                .iter()
+                .all(|(left, right)| left == right)
+    }
+
+    /// Average absolute weight delta of the matching genes, 0 without matching genes
+    pub fn average_weight_difference(&self) -> f64 {
+        if self.matching.is_empty() {
+            return 0f64;
+        }
+        let total = self
+            .matching
+            .iter()
+            .map(|genes| genes.weight_delta().abs())
+            .sum::<f64>();
+        total / self.matching.len() as f64
+    }
+
+    /// Average difference of the neurons in both genomes: absolute bias delta plus
+    /// 1 if the activation function changed
+    pub fn average_neuron_difference(&self) -> f64 {
+        if self.matching_neurons.is_empty() {
+            return 0f64;
+        }
+        let total = self
+            .matching_neurons
+            .iter()
+            .map(|(left, right)| {
+                let activation = if left.activation() == right.activation() {
+                    0f64
+                } else {
+                    1f64
+                };
+                (left.bias() - right.bias()).abs() + activation
+            })
+            .sum::<f64>();
+        total / self.matching_neurons.len() as f64
+    }
+
+    /// Neurons found in only one of the genomes
+    pub fn disjoint_neurons(&self) -> usize {
+        self.added_neurons.len() + self.removed_neurons.len()
+    }
+}
+
+impl fmt::Display for GenomeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} matching, {} disjoint, {} excess genes",
+            self.matching.len(),
+            self.disjoint.len(),
+            self.excess.len()
+        )?;
+        for genes in &self.matching {
+            let delta = genes.weight_delta();
+            if delta.abs() < f64::EPSILON && !genes.enabled_changed() {
+                continue;
+            }
+            write!(
+                f,
+                "  ~ {} -> {} weight {:+.4}",
+                genes.left.in_neuron_id(),
+                genes.left.out_neuron_id(),
+                delta
+            )?;
+            if genes.enabled_changed() {
+                let state = |enabled| if enabled { "enabled" } else { "disabled" };
+                write!(
+                    f,
+                    ", {} -> {}",
+                    state(genes.left.enabled()),
+                    state(genes.right.enabled())
+                )?;
+            }
+            writeln!(f)?;
+        }
+        for (kind, genes) in &[("disjoint", &self.disjoint), ("excess", &self.excess)] {
+            for unmatched in genes.iter() {
+                let sign = match unmatched.side {
+                    DiffSide::Left => '-',
+                    DiffSide::Right => '+',
+                };
+                writeln!(
+                    f,
+                    "  {} {} -> {} weight {:.4} ({})",
+                    sign,
+                    unmatched.gene.in_neuron_id(),
+                    unmatched.gene.out_neuron_id(),
+                    unmatched.gene.weight(),
+                    kind
+                )?;
+            }
+        }
+        for (left, right) in &self.matching_neurons {
+            if left == right {
+                continue;
+            }
+            writeln!(
+                f,
+                "  ~ neuron {} bias {:+.4}, {:?} -> {:?}",
+                left.id(),
+                right.bias() - left.bias(),
+                left.activation(),
+                right.activation()
+            )?;
+        }
+        for node in &self.removed_neurons {
+            writeln!(f, "  - neuron {} {:?}", node.id(), node.role())?;
+        }
+        for node in &self.added_neurons {
+            writeln!(f, "  + neuron {} {:?}", node.id(), node.role())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_gene::NeuronRole;
+
+    #[test]
+    fn diff_should_list_gene_and_neuron_changes() {
+        let left = Genome::new_initialized(2, 1);
+        let mut right = left.clone();
+        right.add_node(NodeGene::new(3, NeuronRole::Hidden));
+        for (in_neuron_id, out_neuron_id, innovation) in &[(0, 3, 3), (3, 2, 4)] {
+            let mut gene = Gene::new(*in_neuron_id, *out_neuron_id, 1f64, true);
+            gene.set_innovation(*innovation);
+            right.add_gene(gene);
+        }
+
+        let diff = GenomeDiff::new(&left, &left);
+        assert!(diff.is_empty());
+        assert_eq!(diff.matching.len(), 2);
+
+        let diff = GenomeDiff::new(&left, &right);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.matching.len(), 2);
+        assert_eq!(diff.disjoint.len(), 0);
+        assert_eq!(diff.excess.len(), 2);
+        assert!(diff.excess.iter().all(|gene| gene.side == DiffSide::Right));
+        assert_eq!(
+            diff.added_neurons,
+            vec![NodeGene::new(3, NeuronRole::Hidden)]
+        );
+        assert!(diff.removed_neurons.is_empty());
+        assert!(format!("{}", diff).contains("+ neuron 3 Hidden"));
+    }
+
+    #[test]
+    fn diff_should_report_weight_deltas_and_enable_changes() {
+        let mut left = Genome::default();
+        left.add_gene(Gene::new(0, 1, 1f64, true));
+        let mut right = Genome::default();
+        right.add_gene(Gene::new(0, 1, 1.5f64, false));
+
+        let diff = GenomeDiff::new(&left, &right);
+        assert_eq!(diff.matching.len(), 1);
+        assert!((diff.matching[0].weight_delta() - 0.5f64).abs() < f64::EPSILON);
+        assert!(diff.matching[0].enabled_changed());
+        assert!((diff.average_weight_difference() - 0.5f64).abs() < f64::EPSILON);
+        assert!(format!("{}", diff).contains("0 -> 1 weight +0.5000, enabled -> disabled"));
+    }
+}
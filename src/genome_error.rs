@@ -0,0 +1,141 @@
+use crate::node_gene::NeuronRole;
+use std::error::Error;
+use std::fmt;
+
+/// Reasons a genome can't be built or doesn't hold the invariants the crate relies on
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenomeError {
+    /// Self connection of a neuron that isn't connected to the rest of the genome
+    DanglingNeuron {
+        /// Highest neuron id a new gene can use
+        max_neuron_id: usize,
+        /// Input neuron of the gene
+        in_neuron_id: usize,
+        /// Output neuron of the gene
+        out_neuron_id: usize,
+    },
+    /// Two genes connect the same neurons
+    DuplicateGene {
+        /// Input neuron of the gene
+        in_neuron_id: usize,
+        /// Output neuron of the gene
+        out_neuron_id: usize,
+    },
+    /// Genes are not sorted by connection
+    UnsortedGenes,
+    /// A neuron id is bigger than the last neuron id of the genome
+    NeuronIdOutOfRange {
+        /// Offending neuron id
+        neuron_id: usize,
+        /// Last neuron id of the genome
+        last_neuron_id: usize,
+    },
+    /// A gene weight is NaN or infinite
+    NonFiniteWeight {
+        /// Input neuron of the gene
+        in_neuron_id: usize,
+        /// Output neuron of the gene
+        out_neuron_id: usize,
+    },
+    /// A gene connects a neuron without node gene
+    MissingNeuron {
+        /// Offending neuron id
+        neuron_id: usize,
+    },
+    /// Neurons are not sorted by id or a neuron id is repeated
+    UnsortedNeurons,
+    /// A neuron has a non finite bias or a non positive time constant
+    InvalidNeuron {
+        /// Offending neuron id
+        neuron_id: usize,
+    },
+    /// A neuron is connected to itself and the recurrence policy forbids it
+    SelfLoop {
+        /// Offending neuron id
+        neuron_id: usize,
+    },
+    /// A gene closes a cycle and the recurrence policy forbids it
+    Cycle {
+        /// Input neuron of the gene
+        in_neuron_id: usize,
+        /// Output neuron of the gene
+        out_neuron_id: usize,
+    },
+    /// The number of input or output neurons doesn't match the genome counts
+    RoleMismatch {
+        /// Role of the neurons
+        role: NeuronRole,
+        /// Count recorded by the genome
+        expected: usize,
+        /// Neurons found with that role
+        found: usize,
+    },
+}
+
+impl fmt::Display for GenomeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GenomeError::DanglingNeuron {
+                max_neuron_id,
+                in_neuron_id,
+                out_neuron_id,
+            } => write!(
+                f,
+                "Try to create a gene neuron unconnected, max neuron id {}, {} -> {}",
+                max_neuron_id, in_neuron_id, out_neuron_id
+            ),
+            GenomeError::DuplicateGene {
+                in_neuron_id,
+                out_neuron_id,
+            } => write!(f, "Duplicate gene {} -> {}", in_neuron_id, out_neuron_id),
+            GenomeError::UnsortedGenes => write!(f, "Genes are not sorted"),
+            GenomeError::NeuronIdOutOfRange {
+                neuron_id,
+                last_neuron_id,
+            } => write!(
+                f,
+                "Neuron id {} is bigger than last neuron id {}",
+                neuron_id, last_neuron_id
+            ),
+            GenomeError::NonFiniteWeight {
+                in_neuron_id,
+                out_neuron_id,
+            } => write!(
+                f,
+                "Gene {} -> {} has a non finite weight",
+                in_neuron_id, out_neuron_id
+            ),
+            GenomeError::MissingNeuron { neuron_id } => {
+                write!(f, "Neuron {} has no node gene", neuron_id)
+            }
+            GenomeError::UnsortedNeurons => write!(f, "Neurons are not sorted by unique id"),
+            GenomeError::InvalidNeuron { neuron_id } => write!(
+                f,
+                "Neuron {} has a non finite bias or a non positive time constant",
+                neuron_id
+            ),
+            GenomeError::SelfLoop { neuron_id } => {
+                write!(f, "Neuron {} is connected to itself", neuron_id)
+            }
+            GenomeError::Cycle {
+                in_neuron_id,
+                out_neuron_id,
+            } => write!(
+                f,
+                "Gene {} -> {} closes a cycle",
+                in_neuron_id, out_neuron_id
+            ),
+            GenomeError::RoleMismatch {
+                role,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Expected {} {:?} neurons, found {}",
+                expected, role, found
+            ),
+        }
+    }
+}
+
+impl Error for GenomeError {}
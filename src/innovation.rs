@@ -0,0 +1,223 @@
+use crate::gene::Gene;
+use crate::genome::Genome;
+use std::collections::{HashMap, HashSet};
+
+/// Population-wide record of structural mutations.
+///
+/// Every new connection `in -> out` receives the same innovation number no matter
+/// which genome creates it, and splitting the same connection in several genomes
+/// during one generation yields the same hidden neuron id, so crossover can line
+/// genes up by their historical marking as described in the NEAT paper.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InnovationTracker {
+    last_innovation: usize,
+    last_neuron_id: usize,
+    #[cfg_attr(feature = "serde", serde(with = "connection_map"))]
+    connections: HashMap<(usize, usize), usize>,
+    #[cfg_attr(feature = "serde", serde(with = "connection_map"))]
+    neuron_splits: HashMap<(usize, usize), usize>,
+    allocated_neurons: HashSet<usize>,
+}
+
+impl InnovationTracker {
+    /// Create an empty tracker
+    pub fn new() -> InnovationTracker {
+        InnovationTracker::default()
+    }
+
+    /// Create a tracker that already knows every innovation of the given genomes
+    pub fn from_genomes<'a, I>(genomes: I) -> InnovationTracker
+    where
+        I: IntoIterator<Item = &'a Genome>,
+    {
+        let mut tracker = InnovationTracker::new();
+        for genome in genomes {
+            tracker.register_genome(genome);
+        }
+        tracker
+    }
+
+    /// Record the innovation numbers and neuron ids used by a genome so new
+    /// mutations don't collide with them.
+    pub fn register_genome(&mut self, genome: &Genome) {
+        for gene in genome.get_genes() {
+            self.register_gene(gene);
+        }
+        if !genome.is_empty() {
+            self.last_neuron_id = self.last_neuron_id.max(genome.len() - 1);
+        }
+    }
+
+    fn register_gene(&mut self, gene: &Gene) {
+        self.last_neuron_id = self
+            .last_neuron_id
+            .max(gene.in_neuron_id())
+            .max(gene.out_neuron_id());
+        if gene.innovation() == 0 {
+            return;
+        }
+        self.connections
+            .entry((gene.in_neuron_id(), gene.out_neuron_id()))
+            .or_insert_with(|| gene.innovation());
+        self.last_innovation = self.last_innovation.max(gene.innovation());
+    }
+
+    /// Renumber the genes of a genome coming from elsewhere, like a seed or a saved
+    /// champion. Connections known to the tracker take its innovation number, new
+    /// connections keep theirs unless another connection already uses it, and
+    /// untracked or clashing genes get a new one.
+    pub fn number_genome(&mut self, genome: &mut Genome) {
+        let genes = genome.get_genes().clone();
+        for gene in genes {
+            let connection = (gene.in_neuron_id(), gene.out_neuron_id());
+            let innovation = match self.connections.get(&connection) {
+                Some(innovation) => *innovation,
+                None if gene.innovation() != 0
+                    && !self
+                        .connections
+                        .values()
+                        .any(|innovation| *innovation == gene.innovation()) =>
+                {
+                    self.register_gene(&gene);
+                    gene.innovation()
+                }
+                None => self.connection_innovation(connection.0, connection.1),
+            };
+            genome
+                .get_gene_mut(connection.0, connection.1)
+                .unwrap()
+                .set_innovation(innovation);
+        }
+        self.register_genome(genome);
+    }
+
+    /// Innovation number of the connection `in_neuron_id -> out_neuron_id`,
+    /// creating a new one the first time the connection appears.
+    pub fn connection_innovation(&mut self, in_neuron_id: usize, out_neuron_id: usize) -> usize {
+        if let Some(innovation) = self.connections.get(&(in_neuron_id, out_neuron_id)) {
+            return *innovation;
+        }
+        self.last_innovation += 1;
+        self.connections
+            .insert((in_neuron_id, out_neuron_id), self.last_innovation);
+        self.last_innovation
+    }
+
+    /// Id of the hidden neuron inserted when splitting `gene`. Genomes splitting
+    /// the same connection in the same generation share the neuron id.
+    pub fn split_neuron_id(&mut self, gene: &Gene) -> usize {
+        let key = (gene.in_neuron_id(), gene.out_neuron_id());
+        if let Some(neuron_id) = self.neuron_splits.get(&key) {
+            return *neuron_id;
+        }
+        let neuron_id = self.new_neuron_id();
+        self.neuron_splits.insert(key, neuron_id);
+        neuron_id
+    }
+
+    /// A neuron id that no genome known by this tracker uses yet
+    pub fn new_neuron_id(&mut self) -> usize {
+        self.last_neuron_id += 1;
+        self.allocated_neurons.insert(self.last_neuron_id);
+        self.last_neuron_id
+    }
+
+    /// True if the neuron was created by this tracker, so genomes that don't
+    /// use it must not connect to it as if it were one of their own neurons.
+    pub fn is_allocated_neuron(&self, neuron_id: usize) -> bool {
+        self.allocated_neurons.contains(&neuron_id)
+    }
+
+    /// Forget the neuron splits of the current generation. Identical splits in
+    /// later generations receive a new neuron id.
+    pub fn new_generation(&mut self) {
+        self.neuron_splits.clear();
+    }
+
+    /// Highest innovation number given so far
+    pub fn last_innovation(&self) -> usize {
+        self.last_innovation
+    }
+
+    /// Highest neuron id known so far
+    pub fn last_neuron_id(&self) -> usize {
+        self.last_neuron_id
+    }
+}
+
+/// Maps keyed by connection are stored as `(in, out, value)` lists, since formats
+/// like JSON only allow string keys
+#[cfg(feature = "serde")]
+mod connection_map {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashMap;
+
+    pub fn serialize<S>(
+        map: &HashMap<(usize, usize), usize>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut entries: Vec<(usize, usize, usize)> = map
+            .iter()
+            .map(|(&(in_neuron_id, out_neuron_id), &value)| (in_neuron_id, out_neuron_id, value))
+            .collect();
+        entries.sort_unstable();
+        entries.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<HashMap<(usize, usize), usize>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let entries = Vec::<(usize, usize, usize)>::deserialize(deserializer)?;
+        Ok(entries
+            .into_iter()
+            .map(|(in_neuron_id, out_neuron_id, value)| ((in_neuron_id, out_neuron_id), value))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_connection_should_get_same_innovation() {
+        let mut tracker = InnovationTracker::new();
+        let first = tracker.connection_innovation(0, 3);
+        let second = tracker.connection_innovation(1, 3);
+        assert_ne!(first, second);
+        assert_eq!(tracker.connection_innovation(0, 3), first);
+    }
+
+    #[test]
+    fn same_split_should_get_same_neuron_until_next_generation() {
+        let mut tracker = InnovationTracker::from_genomes(&[Genome::new_initialized(2, 1)]);
+        let gene = Gene::new(0, 2, 1f64, true);
+        let neuron_id = tracker.split_neuron_id(&gene);
+        assert_eq!(neuron_id, 3);
+        assert_eq!(tracker.split_neuron_id(&gene), neuron_id);
+        assert_ne!(
+            tracker.split_neuron_id(&Gene::new(1, 2, 1f64, true)),
+            neuron_id
+        );
+        tracker.new_generation();
+        assert_ne!(tracker.split_neuron_id(&gene), neuron_id);
+    }
+
+    #[test]
+    fn registered_genome_innovations_should_be_reused() {
+        let genome = Genome::new_initialized(2, 2);
+        let mut tracker = InnovationTracker::from_genomes(Some(&genome));
+        for gene in genome.get_genes() {
+            assert_eq!(
+                tracker.connection_innovation(gene.in_neuron_id(), gene.out_neuron_id()),
+                gene.innovation()
+            );
+        }
+        assert_eq!(tracker.connection_innovation(3, 2), 5);
+    }
+}
@@ -29,32 +29,68 @@ extern crate num_cpus;
 extern crate rand;
 extern crate rulinalg;
 
-#[cfg(feature = "telemetry")]
+#[cfg(feature = "serde")]
 extern crate serde_json;
+#[cfg(feature = "serde")]
+extern crate toml;
 
+pub use self::activation::Activation;
+#[cfg(feature = "serde")]
+pub use self::champion::{Champion, CHAMPION_FORMAT_VERSION};
+pub use self::codegen::{Codegen, CodegenError, TargetLanguage};
+#[cfg(feature = "serde")]
+pub use self::config_file::ConfigReport;
+pub use self::crossover::{Crossover, CrossoverOperator};
 pub use self::ctrnn::Ctrnn;
 pub use self::environment::Environment;
 pub use self::gene::Gene;
 pub use self::genome::Genome;
-pub use self::mutation_config::MutationConfig;
+pub use self::genome_diff::{DiffSide, GenomeDiff, MatchingGenes, UnmatchedGene};
+pub use self::genome_error::GenomeError;
+pub use self::innovation::InnovationTracker;
+pub use self::mutation::{Mutation, MutationRegistry};
+pub use self::mutation_config::{ConfigError, MutationConfig, RecurrencePolicy};
+pub use self::neat_python::{
+    NeatPythonConfig, NeatPythonConnection, NeatPythonError, NeatPythonGenome, NeatPythonNode,
+};
+pub use self::node_gene::{NeuronRole, NodeGene};
 pub use self::organism::Organism;
-pub use self::population::Population;
+pub use self::population::{Population, SeedError};
+pub use self::pruning::{PhasedPruning, PruningPhase};
+pub use self::rng::{EvolutionRng, RngState};
 pub use self::specie::Specie;
 pub use self::species_evaluator::SpeciesEvaluator;
 pub use ctrnn::CtrnnNeuralNetwork;
 
+mod activation;
+#[cfg(feature = "serde")]
+mod champion;
+mod codegen;
+#[cfg(feature = "serde")]
+mod config_file;
+mod crossover;
 mod ctrnn;
+mod dot;
 /// Trait to define test parameter
 pub mod environment;
 mod gene;
 /// A collection of genes
 pub mod genome;
+mod genome_diff;
+mod genome_error;
+/// Population-wide innovation numbers
+pub mod innovation;
 mod mutation;
 /// Configurable mutation rates
 pub mod mutation_config;
+mod neat_python;
+mod node_gene;
 /// A genome plus fitness
 pub mod organism;
 /// A collection of species with champion
 pub mod population;
+mod pruning;
+mod rng;
 mod specie;
 mod species_evaluator;
+mod svg;
@@ -1,42 +1,126 @@
+use crate::activation::Activation;
 use crate::gene::Gene;
+use crate::genome::Genome;
+use crate::innovation::InnovationTracker;
+use crate::mutation_config::MutationConfig;
+use crate::node_gene::NodeGene;
+use crate::rng;
+use std::fmt;
+use std::sync::Arc;
 
-pub trait Mutation {}
+/// A mutation operator applied to offspring on top of the built-in mutations of
+/// `MutationConfig`, registered with its rate in a `MutationRegistry`
+pub trait Mutation: Send + Sync {
+    /// Name of the operator, used to find it in the registry
+    fn name(&self) -> &str;
 
-impl dyn Mutation {
-    pub fn connection_weight(gene: &mut Gene, perturbation: bool) {
-        let mut new_weight = Gene::generate_weight();
-        if perturbation {
-            new_weight += gene.weight();
+    /// Mutate the genome, numbering new genes and neurons with `tracker`
+    fn mutate(&self, genome: &mut Genome, config: &MutationConfig, tracker: &mut InnovationTracker);
+}
+
+/// Mutation operators with the probability of applying each one to a child
+#[derive(Clone, Default)]
+pub struct MutationRegistry {
+    operators: Vec<(Arc<dyn Mutation>, f64)>,
+}
+
+impl MutationRegistry {
+    /// Create an empty registry
+    pub fn new() -> MutationRegistry {
+        MutationRegistry::default()
+    }
+
+    /// Add an operator applied with probability `rate`, replacing any operator
+    /// with the same name
+    pub fn register<M: Mutation + 'static>(&mut self, operator: M, rate: f64) {
+        self.remove(operator.name());
+        self.operators.push((Arc::new(operator), rate));
+    }
+
+    /// Remove the operator with the given name, true if it was registered
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.operators.len();
+        self.operators
+            .retain(|(operator, _)| operator.name() != name);
+        self.operators.len() != len
+    }
+
+    /// Change the rate of the operator with the given name, true if it was registered
+    pub fn set_rate(&mut self, name: &str, rate: f64) -> bool {
+        match self
+            .operators
+            .iter_mut()
+            .find(|(operator, _)| operator.name() == name)
+        {
+            Some(entry) => {
+                entry.1 = rate;
+                true
+            }
+            None => false,
         }
-        gene.set_weight(new_weight);
     }
 
-    pub fn add_connection(in_neuron_id: usize, out_neuron_id: usize) -> Gene {
-        Gene::new(
-            in_neuron_id,
-            out_neuron_id,
-            Gene::generate_weight(),
-            true,
-            false,
-        )
+    /// Rate of the operator with the given name
+    pub fn rate(&self, name: &str) -> Option<f64> {
+        self.operators
+            .iter()
+            .find(|(operator, _)| operator.name() == name)
+            .map(|(_, rate)| *rate)
+    }
+
+    /// Number of registered operators
+    pub fn len(&self) -> usize {
+        self.operators.len()
+    }
+
+    /// True if no operator is registered
+    pub fn is_empty(&self) -> bool {
+        self.operators.is_empty()
+    }
+
+    /// Apply each operator, in registration order, with its rate
+    pub fn apply(
+        &self,
+        genome: &mut Genome,
+        config: &MutationConfig,
+        tracker: &mut InnovationTracker,
+    ) {
+        for (operator, rate) in &self.operators {
+            if rng::random::<f64>() < *rate {
+                operator.mutate(genome, config, tracker);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for MutationRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.operators
+                    .iter()
+                    .map(|(operator, rate)| (operator.name().to_owned(), rate)),
+            )
+            .finish()
+    }
+}
+
+impl dyn Mutation {
+    #[cfg(test)]
+    pub(crate) fn add_connection(in_neuron_id: usize, out_neuron_id: usize) -> Gene {
+        Gene::new(in_neuron_id, out_neuron_id, Gene::generate_weight(), true)
     }
 
-    pub fn add_neuron(gene: &mut Gene, new_neuron_id: usize) -> (Gene, Gene) {
+    pub(crate) fn add_neuron(gene: &mut Gene, new_neuron_id: usize) -> (Gene, Gene) {
         gene.set_disabled();
 
-        let gen1 = Gene::new(gene.in_neuron_id(), new_neuron_id, 1f64, true, false);
+        let gen1 = Gene::new(gene.in_neuron_id(), new_neuron_id, 1f64, true);
 
-        let gen2 = Gene::new(
-            new_neuron_id,
-            gene.out_neuron_id(),
-            gene.weight(),
-            true,
-            false,
-        );
+        let gen2 = Gene::new(new_neuron_id, gene.out_neuron_id(), gene.weight(), true);
         (gen1, gen2)
     }
 
-    pub fn toggle_expression(gene: &mut Gene) {
+    pub(crate) fn toggle_expression(gene: &mut Gene) {
         if gene.enabled() {
             gene.set_disabled()
         } else {
@@ -44,23 +128,89 @@ impl dyn Mutation {
         }
     }
 
-    pub fn toggle_bias(gene: &mut Gene) {
-        if gene.is_bias() {
-            gene.set_bias(false)
-        } else {
-            gene.set_bias(true)
+    /// Add a random value in [-power, power] to the time constant of the neuron,
+    /// keeping it within [min, max]
+    pub(crate) fn perturb_tau(node: &mut NodeGene, power: f64, min: f64, max: f64) {
+        let perturbation = (rng::random::<f64>() * 2f64 - 1f64) * power;
+        node.set_tau((node.tau() + perturbation).max(min).min(max));
+    }
+
+    /// Replace the activation function of the neuron with another one of `allowed`
+    pub(crate) fn change_activation(node: &mut NodeGene, allowed: &[Activation]) {
+        let candidates: Vec<&Activation> = allowed
+            .iter()
+            .filter(|activation| **activation != node.activation())
+            .collect();
+        if candidates.is_empty() {
+            return;
         }
+        let mut rng = rng::thread_rng();
+        let selected = rand::seq::sample_iter(&mut rng, candidates, 1).unwrap()[0];
+        node.set_activation(*selected);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::gene::Gene;
+    use crate::node_gene::NeuronRole;
+
+    /// Swap the weights of two random genes
+    struct SwapWeights;
+
+    impl Mutation for SwapWeights {
+        fn name(&self) -> &str {
+            "swap_weights"
+        }
+
+        fn mutate(&self, genome: &mut Genome, _: &MutationConfig, _: &mut InnovationTracker) {
+            let connections: Vec<(usize, usize, f64)> = genome
+                .get_genes()
+                .iter()
+                .map(|gene| (gene.in_neuron_id(), gene.out_neuron_id(), gene.weight()))
+                .collect();
+            if connections.len() < 2 {
+                return;
+            }
+            let (first, second) = (connections[0], connections[connections.len() - 1]);
+            genome
+                .get_gene_mut(first.0, first.1)
+                .unwrap()
+                .set_weight(second.2);
+            genome
+                .get_gene_mut(second.0, second.1)
+                .unwrap()
+                .set_weight(first.2);
+        }
+    }
+
+    #[test]
+    fn registry_should_apply_operators_with_their_rate() {
+        let mut genome = Genome::default();
+        genome.add_gene(Gene::new(0, 1, 1f64, true));
+        genome.add_gene(Gene::new(0, 2, 2f64, true));
+        let config = MutationConfig::default();
+        let mut tracker = InnovationTracker::new();
+
+        let mut registry = MutationRegistry::new();
+        registry.register(SwapWeights, 0f64);
+        registry.apply(&mut genome, &config, &mut tracker);
+        assert!((genome.get_genes()[0].weight() - 1f64).abs() < f64::EPSILON);
+
+        assert!(registry.set_rate("swap_weights", 1f64));
+        registry.apply(&mut genome, &config, &mut tracker);
+        assert!((genome.get_genes()[0].weight() - 2f64).abs() < f64::EPSILON);
+
+        registry.register(SwapWeights, 0.5);
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.rate("swap_weights"), Some(0.5));
+        assert!(registry.remove("swap_weights"));
+        assert!(registry.is_empty());
+    }
 
     #[test]
     fn mutate_toggle_gene_should_toggle() {
-        let mut gene = Gene::new(0, 1, 1f64, false, false);
+        let mut gene = Gene::new(0, 1, 1f64, false);
 
         <dyn Mutation>::toggle_expression(&mut gene);
         assert!(gene.enabled());
@@ -68,4 +218,25 @@ mod tests {
         <dyn Mutation>::toggle_expression(&mut gene);
         assert!(!gene.enabled());
     }
+
+    #[test]
+    fn mutate_tau_should_stay_within_bounds() {
+        let mut node = NodeGene::new(0, NeuronRole::Hidden);
+        for _ in 0..100 {
+            <dyn Mutation>::perturb_tau(&mut node, 0.5, 0.01, 0.2);
+            assert!(node.tau() >= 0.01 && node.tau() <= 0.2);
+        }
+    }
+
+    #[test]
+    fn mutate_activation_should_pick_another_allowed_activation() {
+        let mut node = NodeGene::new(0, NeuronRole::Hidden);
+        let allowed = [Activation::Sigmoid, Activation::Tanh];
+
+        <dyn Mutation>::change_activation(&mut node, &allowed);
+        assert_eq!(node.activation(), Activation::Tanh);
+
+        <dyn Mutation>::change_activation(&mut node, &[Activation::Tanh]);
+        assert_eq!(node.activation(), Activation::Tanh);
+    }
 }
@@ -1,13 +1,77 @@
+use crate::activation::Activation;
+use crate::crossover::CrossoverOperator;
+use std::error::Error;
+use std::fmt;
+
 use crate::genome::{
-    COMPATIBILITY_THRESHOLD, MUTATE_ADD_CONNECTION, MUTATE_ADD_NEURON, MUTATE_CONNECTION_WEIGHT,
-    MUTATE_CONNECTION_WEIGHT_PERTURBED_PROBABILITY, MUTATE_TOGGLE_BIAS, MUTATE_TOGGLE_EXPRESSION,
+    COMPATIBILITY_THRESHOLD, MUTATE_ACTIVATION, MUTATE_ADD_CONNECTION, MUTATE_ADD_NEURON,
+    MUTATE_BIAS, MUTATE_BIAS_REPLACE_PROBABILITY, MUTATE_CONNECTION_WEIGHT,
+    MUTATE_CONNECTION_WEIGHT_PERTURBED_PROBABILITY, MUTATE_TOGGLE_EXPRESSION,
 };
 
+/// Loops structural mutations may create
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RecurrencePolicy {
+    /// Any connection, including self-loops and cycles
+    #[default]
+    Recurrent,
+    /// Cycles through other neurons, but no neuron connected to itself
+    NoSelfLoops,
+    /// Neurons connected to themselves, but no cycle through other neurons
+    SelfLoopsOnly,
+    /// No loops at all
+    Acyclic,
+}
+
+impl RecurrencePolicy {
+    /// True if a neuron may connect to itself
+    pub fn allows_self_loops(self) -> bool {
+        match self {
+            RecurrencePolicy::Recurrent | RecurrencePolicy::SelfLoopsOnly => true,
+            RecurrencePolicy::NoSelfLoops | RecurrencePolicy::Acyclic => false,
+        }
+    }
+
+    /// True if connections may form cycles through several neurons
+    pub fn allows_cycles(self) -> bool {
+        match self {
+            RecurrencePolicy::Recurrent | RecurrencePolicy::NoSelfLoops => true,
+            RecurrencePolicy::SelfLoopsOnly | RecurrencePolicy::Acyclic => false,
+        }
+    }
+}
+
+/// A setting of `MutationConfig` outside the values it accepts
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    /// Name of the setting
+    pub field: &'static str,
+    /// Value found
+    pub value: f64,
+    /// Values the setting accepts
+    pub expected: &'static str,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} is {} but must be {}",
+            self.field, self.value, self.expected
+        )
+    }
+}
+
+impl Error for ConfigError {}
+
 /// Configuration for mutation rates in NEAT
 ///
 /// Allows customizing mutation probabilities per problem.
 /// Use `MutationConfig::default()` for standard NEAT values defined in `genome.rs`.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct MutationConfig {
     /// Probability of mutating connection weights
     pub weight_mutation_rate: f64,
@@ -15,21 +79,60 @@ pub struct MutationConfig {
     pub add_connection_rate: f64,
     /// Probability of adding a new neuron
     pub add_neuron_rate: f64,
+    /// Probability of removing a connection (default 0)
+    pub delete_connection_rate: f64,
+    /// Probability of removing a hidden neuron and its connections (default 0)
+    pub delete_neuron_rate: f64,
     /// Probability of toggling a connection's enabled state
     pub toggle_expression_rate: f64,
     /// Probability of perturbing vs replacing weight
     pub weight_perturbation_rate: f64,
-    /// Probability of toggling bias
-    pub toggle_bias_rate: f64,
+    /// Probability of mutating neuron biases
+    pub bias_mutation_rate: f64,
+    /// Probability of replacing vs perturbing a bias when biases mutate
+    pub bias_replace_rate: f64,
+    /// Range for initial and replaced biases: [-bias_init_range, bias_init_range]
+    /// (default 0, new neurons start without bias)
+    pub bias_init_range: f64,
+    /// Power of bias perturbation: random value in [-bias_mutate_power, bias_mutate_power]
+    pub bias_mutate_power: f64,
+    /// Probability of changing the activation function of a neuron
+    pub activation_mutation_rate: f64,
+    /// Activation functions a neuron can mutate to (default only sigmoid)
+    pub allowed_activations: Vec<Activation>,
     /// Compatibility threshold for speciation
     pub compatibility_threshold: f64,
+    /// Generations without improvement after which a species is removed (default 15)
+    pub max_stagnation: usize,
+    /// Number of best species never removed for stagnation (default 2)
+    pub species_elitism: usize,
+    /// Compatibility coefficient c1 of excess genes
+    pub compatibility_excess_coefficient: f64,
+    /// Compatibility coefficient c2 of disjoint genes
+    pub compatibility_disjoint_coefficient: f64,
+    /// Compatibility coefficient c3 of the average weight difference of matching genes
+    pub compatibility_weight_coefficient: f64,
+    /// Compatibility coefficient of the average bias and activation difference of
+    /// neurons found in both genomes (default 0.2, like c3)
+    pub compatibility_neuron_difference_coefficient: f64,
+    /// Compatibility coefficient of neurons found in only one genome (default 0,
+    /// hidden neurons already come with disjoint genes)
+    pub compatibility_node_coefficient: f64,
+    /// Genomes up to this size aren't normalized by their number of genes (default 0,
+    /// always normalize; the NEAT paper uses 20)
+    pub compatibility_normalize_threshold: usize,
     /// Probability of mutating vs crossing over (0.0 = all crossover, 1.0 = all mutation)
     pub mutation_probability: f64,
+    /// Crossover operators with their weights, one is picked per crossover in
+    /// proportion to its weight (default 40% average, 60% uniform as in the NEAT paper)
+    pub crossover_operators: Vec<(CrossoverOperator, f64)>,
+    /// Probability of a gene disabled in any parent staying disabled in the child
+    pub crossover_disable_rate: f64,
     /// Range for initial weight generation: [-weight_init_range, weight_init_range]
     pub weight_init_range: f64,
     /// Power of weight perturbation: random value in [-weight_mutate_power, weight_mutate_power]
     pub weight_mutate_power: f64,
-    /// CTRNN neuron time constant τ (default 0.01).
+    /// Initial CTRNN neuron time constant τ (default 0.01).
     /// Represents how fast neurons respond — like biological membrane resistance time.
     /// Small τ (e.g. 0.01): neurons react instantly, network behaves as feedforward.
     /// Large τ (e.g. 0.1-1.0): neurons have inertia, network retains temporal memory.
@@ -39,6 +142,25 @@ pub struct MutationConfig {
     /// With dt=0.01, this means 10 Euler integration steps per activation.
     /// More time = more steps = more accurate convergence but slower execution.
     pub step_time: f64,
+    /// Neurons start with a time constant in [tau - tau_init_range, tau + tau_init_range]
+    /// (default 0, every neuron starts with `tau`).
+    pub tau_init_range: f64,
+    /// Probability of perturbing the time constant of a neuron (default 0, time
+    /// constants don't evolve)
+    pub tau_mutation_rate: f64,
+    /// Power of time constant perturbation: random value in [-tau_mutate_power, tau_mutate_power]
+    pub tau_mutate_power: f64,
+    /// Lowest time constant a neuron can get
+    pub tau_min: f64,
+    /// Highest time constant a mutation can reach
+    pub tau_max: f64,
+    /// Only allow connections that keep the network acyclic and evaluate it once in
+    /// topological order instead of simulating the CTRNN (default false).
+    /// Use it for static tasks like classification or regression.
+    pub feed_forward: bool,
+    /// Loops structural mutations and crossover may create (default recurrent).
+    /// Feed-forward mode always uses `RecurrencePolicy::Acyclic`.
+    pub recurrence_policy: RecurrencePolicy,
 }
 
 impl Default for MutationConfig {
@@ -47,40 +169,174 @@ impl Default for MutationConfig {
             weight_mutation_rate: MUTATE_CONNECTION_WEIGHT,
             add_connection_rate: MUTATE_ADD_CONNECTION,
             add_neuron_rate: MUTATE_ADD_NEURON,
+            delete_connection_rate: 0.0,
+            delete_neuron_rate: 0.0,
             toggle_expression_rate: MUTATE_TOGGLE_EXPRESSION,
             weight_perturbation_rate: MUTATE_CONNECTION_WEIGHT_PERTURBED_PROBABILITY,
-            toggle_bias_rate: MUTATE_TOGGLE_BIAS,
+            bias_mutation_rate: MUTATE_BIAS,
+            bias_replace_rate: MUTATE_BIAS_REPLACE_PROBABILITY,
+            bias_init_range: 0.0,
+            bias_mutate_power: 0.5,
+            activation_mutation_rate: MUTATE_ACTIVATION,
+            allowed_activations: vec![Activation::Sigmoid],
             compatibility_threshold: COMPATIBILITY_THRESHOLD,
+            max_stagnation: 15,
+            species_elitism: 2,
+            compatibility_excess_coefficient: 1.0,
+            compatibility_disjoint_coefficient: 1.0,
+            compatibility_weight_coefficient: 0.2,
+            compatibility_neuron_difference_coefficient: 0.2,
+            compatibility_node_coefficient: 0.0,
+            compatibility_normalize_threshold: 0,
             mutation_probability: 0.4,
+            crossover_operators: vec![
+                (CrossoverOperator::Average, 0.4),
+                (CrossoverOperator::Uniform, 0.6),
+            ],
+            crossover_disable_rate: 0.25,
             weight_init_range: 1.0,
             weight_mutate_power: 1.0,
             tau: 0.01,
             step_time: 0.1,
+            tau_init_range: 0.0,
+            tau_mutation_rate: 0.0,
+            tau_mutate_power: 0.05,
+            tau_min: 0.001,
+            tau_max: 10.0,
+            feed_forward: false,
+            recurrence_policy: RecurrencePolicy::Recurrent,
         }
     }
 }
 
 impl MutationConfig {
     /// Create a new configuration with custom values
+    #[allow(clippy::new_ret_no_self)]
     pub fn new() -> MutationConfigBuilder {
         MutationConfigBuilder::default()
     }
+
+    /// Check every setting is within the values it accepts: probabilities in
+    /// [0, 1], ranges, powers and coefficients not negative, time constants
+    /// positive with `tau_min <= tau_max`, and at least one integration step of
+    /// 0.01 per activation.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let check = |field, value: f64, valid: bool, expected| {
+            if valid && value.is_finite() {
+                Ok(())
+            } else {
+                Err(ConfigError {
+                    field,
+                    value,
+                    expected,
+                })
+            }
+        };
+        for (field, value) in &[
+            ("weight_mutation_rate", self.weight_mutation_rate),
+            ("add_connection_rate", self.add_connection_rate),
+            ("add_neuron_rate", self.add_neuron_rate),
+            ("delete_connection_rate", self.delete_connection_rate),
+            ("delete_neuron_rate", self.delete_neuron_rate),
+            ("toggle_expression_rate", self.toggle_expression_rate),
+            ("weight_perturbation_rate", self.weight_perturbation_rate),
+            ("bias_mutation_rate", self.bias_mutation_rate),
+            ("bias_replace_rate", self.bias_replace_rate),
+            ("activation_mutation_rate", self.activation_mutation_rate),
+            ("mutation_probability", self.mutation_probability),
+            ("crossover_disable_rate", self.crossover_disable_rate),
+            ("tau_mutation_rate", self.tau_mutation_rate),
+        ] {
+            check(
+                field,
+                *value,
+                (0f64..=1f64).contains(value),
+                "a probability in [0, 1]",
+            )?;
+        }
+        for (field, value) in &[
+            ("bias_init_range", self.bias_init_range),
+            ("bias_mutate_power", self.bias_mutate_power),
+            ("weight_init_range", self.weight_init_range),
+            ("weight_mutate_power", self.weight_mutate_power),
+            ("tau_init_range", self.tau_init_range),
+            ("tau_mutate_power", self.tau_mutate_power),
+            ("compatibility_threshold", self.compatibility_threshold),
+            (
+                "compatibility_excess_coefficient",
+                self.compatibility_excess_coefficient,
+            ),
+            (
+                "compatibility_disjoint_coefficient",
+                self.compatibility_disjoint_coefficient,
+            ),
+            (
+                "compatibility_weight_coefficient",
+                self.compatibility_weight_coefficient,
+            ),
+            (
+                "compatibility_neuron_difference_coefficient",
+                self.compatibility_neuron_difference_coefficient,
+            ),
+            (
+                "compatibility_node_coefficient",
+                self.compatibility_node_coefficient,
+            ),
+        ] {
+            check(field, *value, *value >= 0f64, "at least 0")?;
+        }
+        for (operator, weight) in &self.crossover_operators {
+            check(
+                "crossover_operators weight",
+                *weight,
+                *weight >= 0f64,
+                "at least 0",
+            )?;
+            match operator {
+                CrossoverOperator::Blend { alpha } => {
+                    check("Blend alpha", *alpha, *alpha >= 0f64, "at least 0")?
+                }
+                CrossoverOperator::MultiParent { parents } => check(
+                    "MultiParent parents",
+                    *parents as f64,
+                    *parents >= 2,
+                    "at least 2",
+                )?,
+                _ => {}
+            }
+        }
+        check("tau", self.tau, self.tau > 0f64, "positive")?;
+        check("tau_min", self.tau_min, self.tau_min > 0f64, "positive")?;
+        check(
+            "tau_max",
+            self.tau_max,
+            self.tau_max >= self.tau_min,
+            "at least tau_min",
+        )?;
+        check(
+            "step_time",
+            self.step_time,
+            self.step_time >= 0.01,
+            "at least the 0.01 integration step",
+        )
+    }
+
+    /// Recurrence policy genomes must follow, acyclic in feed-forward mode
+    pub fn effective_recurrence_policy(&self) -> RecurrencePolicy {
+        if self.feed_forward {
+            RecurrencePolicy::Acyclic
+        } else {
+            self.recurrence_policy
+        }
+    }
 }
 
 /// Builder for MutationConfig
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Default, Clone)]
 pub struct MutationConfigBuilder {
     config: MutationConfig,
 }
 
-impl Default for MutationConfigBuilder {
-    fn default() -> Self {
-        MutationConfigBuilder {
-            config: MutationConfig::default(),
-        }
-    }
-}
-
 impl MutationConfigBuilder {
     /// Set weight mutation rate
     pub fn weight_mutation_rate(mut self, rate: f64) -> Self {
@@ -100,6 +356,18 @@ impl MutationConfigBuilder {
         self
     }
 
+    /// Set delete connection rate
+    pub fn delete_connection_rate(mut self, rate: f64) -> Self {
+        self.config.delete_connection_rate = rate;
+        self
+    }
+
+    /// Set delete neuron rate
+    pub fn delete_neuron_rate(mut self, rate: f64) -> Self {
+        self.config.delete_neuron_rate = rate;
+        self
+    }
+
     /// Set toggle expression rate
     pub fn toggle_expression_rate(mut self, rate: f64) -> Self {
         self.config.toggle_expression_rate = rate;
@@ -113,8 +381,44 @@ impl MutationConfigBuilder {
     }
 
     /// Set toggle bias rate
-    pub fn toggle_bias_rate(mut self, rate: f64) -> Self {
-        self.config.toggle_bias_rate = rate;
+    #[deprecated(since = "0.3.0", note = "please use `bias_mutation_rate` instead")]
+    pub fn toggle_bias_rate(self, rate: f64) -> Self {
+        self.bias_mutation_rate(rate)
+    }
+
+    /// Set bias mutation rate
+    pub fn bias_mutation_rate(mut self, rate: f64) -> Self {
+        self.config.bias_mutation_rate = rate;
+        self
+    }
+
+    /// Set probability of replacing vs perturbing a bias
+    pub fn bias_replace_rate(mut self, rate: f64) -> Self {
+        self.config.bias_replace_rate = rate;
+        self
+    }
+
+    /// Set bias initialization range [-range, range]
+    pub fn bias_init_range(mut self, range: f64) -> Self {
+        self.config.bias_init_range = range;
+        self
+    }
+
+    /// Set bias perturbation power [-power, power]
+    pub fn bias_mutate_power(mut self, power: f64) -> Self {
+        self.config.bias_mutate_power = power;
+        self
+    }
+
+    /// Set activation mutation rate
+    pub fn activation_mutation_rate(mut self, rate: f64) -> Self {
+        self.config.activation_mutation_rate = rate;
+        self
+    }
+
+    /// Set the activation functions neurons can mutate to
+    pub fn allowed_activations(mut self, activations: Vec<Activation>) -> Self {
+        self.config.allowed_activations = activations;
         self
     }
 
@@ -124,12 +428,63 @@ impl MutationConfigBuilder {
         self
     }
 
+    /// Set generations without improvement after which a species is removed
+    pub fn max_stagnation(mut self, generations: usize) -> Self {
+        self.config.max_stagnation = generations;
+        self
+    }
+
+    /// Set number of best species protected from stagnation removal
+    pub fn species_elitism(mut self, species: usize) -> Self {
+        self.config.species_elitism = species;
+        self
+    }
+
+    /// Set compatibility coefficients c1 (excess), c2 (disjoint) and c3 (weights)
+    pub fn compatibility_coefficients(mut self, excess: f64, disjoint: f64, weight: f64) -> Self {
+        self.config.compatibility_excess_coefficient = excess;
+        self.config.compatibility_disjoint_coefficient = disjoint;
+        self.config.compatibility_weight_coefficient = weight;
+        self
+    }
+
+    /// Set compatibility coefficient of the bias and activation difference of
+    /// matching neurons
+    pub fn compatibility_neuron_difference_coefficient(mut self, coefficient: f64) -> Self {
+        self.config.compatibility_neuron_difference_coefficient = coefficient;
+        self
+    }
+
+    /// Set compatibility coefficient of disjoint neurons
+    pub fn compatibility_node_coefficient(mut self, coefficient: f64) -> Self {
+        self.config.compatibility_node_coefficient = coefficient;
+        self
+    }
+
+    /// Set size up to which compatibility isn't normalized by genome size
+    pub fn compatibility_normalize_threshold(mut self, threshold: usize) -> Self {
+        self.config.compatibility_normalize_threshold = threshold;
+        self
+    }
+
     /// Set mutation vs crossover probability (0.4 = 40% mutation, 60% crossover)
     pub fn mutation_probability(mut self, prob: f64) -> Self {
         self.config.mutation_probability = prob;
         self
     }
 
+    /// Set crossover operators and their weights
+    pub fn crossover_operators(mut self, operators: Vec<(CrossoverOperator, f64)>) -> Self {
+        self.config.crossover_operators = operators;
+        self
+    }
+
+    /// Set probability of inherited disabled genes staying disabled
+    pub fn crossover_disable_rate(mut self, rate: f64) -> Self {
+        self.config.crossover_disable_rate = rate;
+        self
+    }
+
     /// Set weight initialization range [-range, range]
     pub fn weight_init_range(mut self, range: f64) -> Self {
         self.config.weight_init_range = range;
@@ -154,8 +509,71 @@ impl MutationConfigBuilder {
         self
     }
 
+    /// Set range of initial time constants around `tau`
+    pub fn tau_init_range(mut self, range: f64) -> Self {
+        self.config.tau_init_range = range;
+        self
+    }
+
+    /// Set time constant mutation rate
+    pub fn tau_mutation_rate(mut self, rate: f64) -> Self {
+        self.config.tau_mutation_rate = rate;
+        self
+    }
+
+    /// Set time constant perturbation power [-power, power]
+    pub fn tau_mutate_power(mut self, power: f64) -> Self {
+        self.config.tau_mutate_power = power;
+        self
+    }
+
+    /// Set bounds of the time constants
+    pub fn tau_bounds(mut self, min: f64, max: f64) -> Self {
+        self.config.tau_min = min;
+        self.config.tau_max = max;
+        self
+    }
+
+    /// Set strict feed-forward mode
+    pub fn feed_forward(mut self, feed_forward: bool) -> Self {
+        self.config.feed_forward = feed_forward;
+        self
+    }
+
+    /// Set loops structural mutations may create
+    pub fn recurrence_policy(mut self, policy: RecurrencePolicy) -> Self {
+        self.config.recurrence_policy = policy;
+        self
+    }
+
     /// Build the configuration
     pub fn build(self) -> MutationConfig {
         self.config
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_should_reject_out_of_range_settings() {
+        assert_eq!(MutationConfig::default().validate(), Ok(()));
+        let error = MutationConfig::new()
+            .add_neuron_rate(-0.1)
+            .build()
+            .validate()
+            .unwrap_err();
+        assert_eq!(error.field, "add_neuron_rate");
+        assert_eq!(
+            error.to_string(),
+            "add_neuron_rate is -0.1 but must be a probability in [0, 1]"
+        );
+        let config = MutationConfig::new().step_time(0.001).build();
+        assert_eq!(config.validate().unwrap_err().field, "step_time");
+        let config = MutationConfig::new()
+            .crossover_operators(vec![(CrossoverOperator::MultiParent { parents: 1 }, 1f64)])
+            .build();
+        assert_eq!(config.validate().unwrap_err().field, "MultiParent parents");
+    }
+}
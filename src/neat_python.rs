@@ -0,0 +1,995 @@
+use crate::activation::Activation;
+use crate::gene::Gene;
+use crate::genome::Genome;
+use crate::genome_error::GenomeError;
+use crate::mutation_config::MutationConfig;
+use crate::node_gene::{NeuronRole, NodeGene};
+use crate::population::Population;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const DEFAULT_POPULATION_SIZE: usize = 150;
+
+/// Error reading a neat-python config or converting a neat-python genome
+#[derive(Debug)]
+pub enum NeatPythonError {
+    /// The file could not be read or written
+    Io(io::Error),
+    /// Line of the config that is not a section, a `key = value` pair or a comment
+    Syntax {
+        /// Line number, starting at 1
+        line: usize,
+        /// Content of the line
+        content: String,
+    },
+    /// Value that cannot be read for its key
+    InvalidValue {
+        /// `Section.key` of the value
+        key: String,
+        /// Value found
+        value: String,
+    },
+    /// Key the config must have
+    MissingKey(String),
+    /// Activation or aggregation function without an equivalent in this crate
+    UnsupportedFunction(String),
+    /// Connection from or to a neuron missing from the genome
+    UnknownNode(i64),
+    /// Only genomes with input and output roles can be exported
+    MissingRoles,
+    /// The converted genome is not valid
+    Genome(GenomeError),
+}
+
+impl fmt::Display for NeatPythonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NeatPythonError::Io(error) => write!(f, "{}", error),
+            NeatPythonError::Syntax { line, content } => {
+                write!(f, "Cannot parse line {}: {}", line, content)
+            }
+            NeatPythonError::InvalidValue { key, value } => {
+                write!(f, "Invalid value {} for {}", value, key)
+            }
+            NeatPythonError::MissingKey(key) => write!(f, "Missing {}", key),
+            NeatPythonError::UnsupportedFunction(function) => {
+                write!(f, "Function {} has no equivalent", function)
+            }
+            NeatPythonError::UnknownNode(key) => write!(f, "Node {} is not in the genome", key),
+            NeatPythonError::MissingRoles => {
+                write!(f, "Genome has no input and output neuron roles")
+            }
+            NeatPythonError::Genome(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for NeatPythonError {}
+
+impl From<io::Error> for NeatPythonError {
+    fn from(error: io::Error) -> NeatPythonError {
+        NeatPythonError::Io(error)
+    }
+}
+
+impl From<GenomeError> for NeatPythonError {
+    fn from(error: GenomeError) -> NeatPythonError {
+        NeatPythonError::Genome(error)
+    }
+}
+
+/// Settings of a neat-python INI config mapped onto this crate.
+///
+/// Gaussian standard deviations of neat-python become uniform ranges with the
+/// same variance, and its separate perturb and replace rates become a mutation
+/// rate with the share of replacements. Keys without equivalent, or whose value
+/// behaves differently than this crate, are listed in `unsupported` and
+/// otherwise ignored.
+#[derive(Debug, Clone)]
+pub struct NeatPythonConfig {
+    /// Mutation, speciation and stagnation settings
+    pub mutation_config: MutationConfig,
+    /// `pop_size`
+    pub population_size: usize,
+    /// Fitness to stop at, None with `no_fitness_termination`
+    pub fitness_threshold: Option<f64>,
+    /// `num_inputs`
+    pub input_neurons: usize,
+    /// `num_outputs`
+    pub output_neurons: usize,
+    /// Genomes start fully connected, false with `initial_connection = unconnected`
+    pub initially_connected: bool,
+    /// `Section.key = value` of the settings that were not applied
+    pub unsupported: Vec<String>,
+}
+
+/// Values of an INI file by (section, key), taken as they are applied
+struct IniValues {
+    values: BTreeMap<(String, String), String>,
+}
+
+impl IniValues {
+    fn parse(content: &str) -> Result<IniValues, NeatPythonError> {
+        let mut values = BTreeMap::new();
+        let mut section = String::new();
+        let mut last_key: Option<(String, String)> = None;
+        for (number, line) in content.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+                continue;
+            }
+            if line.starts_with(char::is_whitespace) {
+                // Continuation of a multi-line value
+                if let Some(key) = &last_key {
+                    let value: &mut String = values.get_mut(key).unwrap();
+                    value.push(' ');
+                    value.push_str(trimmed);
+                    continue;
+                }
+            }
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                section = trimmed[1..trimmed.len() - 1].trim().to_string();
+                last_key = None;
+                continue;
+            }
+            match trimmed.find(['=', ':']) {
+                Some(position) if !section.is_empty() => {
+                    let key = (section.clone(), trimmed[..position].trim().to_lowercase());
+                    values.insert(key.clone(), trimmed[position + 1..].trim().to_string());
+                    last_key = Some(key);
+                }
+                _ => {
+                    return Err(NeatPythonError::Syntax {
+                        line: number + 1,
+                        content: line.to_string(),
+                    })
+                }
+            }
+        }
+        Ok(IniValues { values })
+    }
+
+    fn take(&mut self, section: &str, key: &str) -> Option<String> {
+        self.values.remove(&(section.to_string(), key.to_string()))
+    }
+
+    fn take_parsed<T: std::str::FromStr>(
+        &mut self,
+        section: &str,
+        key: &str,
+    ) -> Result<Option<T>, NeatPythonError> {
+        match self.take(section, key) {
+            None => Ok(None),
+            Some(value) => {
+                value
+                    .parse::<T>()
+                    .map(Some)
+                    .map_err(|_| NeatPythonError::InvalidValue {
+                        key: format!("{}.{}", section, key),
+                        value,
+                    })
+            }
+        }
+    }
+
+    fn take_bool(&mut self, section: &str, key: &str) -> Result<Option<bool>, NeatPythonError> {
+        match self.take(section, key) {
+            None => Ok(None),
+            Some(value) => match value.to_lowercase().as_str() {
+                "true" | "1" | "yes" | "on" => Ok(Some(true)),
+                "false" | "0" | "no" | "off" => Ok(Some(false)),
+                _ => Err(NeatPythonError::InvalidValue {
+                    key: format!("{}.{}", section, key),
+                    value,
+                }),
+            },
+        }
+    }
+
+    // Take a key that has no setting of its own, reporting it unless the value
+    // behaves like this crate
+    fn take_expected<F: Fn(&str) -> bool>(
+        &mut self,
+        section: &str,
+        key: &str,
+        expected: F,
+        unsupported: &mut Vec<String>,
+    ) {
+        if let Some(value) = self.take(section, key) {
+            if !expected(&value) {
+                unsupported.push(format!("{}.{} = {}", section, key, value));
+            }
+        }
+    }
+}
+
+fn is_zero(value: &str) -> bool {
+    value.parse::<f64>() == Ok(0f64)
+}
+
+// Uniform range with the variance of a gaussian distribution of this deviation
+fn uniform_range(stdev: f64) -> f64 {
+    stdev * 3f64.sqrt()
+}
+
+impl NeatPythonConfig {
+    /// Read a neat-python config file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<NeatPythonConfig, NeatPythonError> {
+        NeatPythonConfig::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parse the content of a neat-python config. `num_inputs` and
+    /// `num_outputs` are required, missing keys keep the defaults of this crate.
+    pub fn parse(content: &str) -> Result<NeatPythonConfig, NeatPythonError> {
+        const NEAT: &str = "NEAT";
+        const GENOME: &str = "DefaultGenome";
+        const SPECIES: &str = "DefaultSpeciesSet";
+        const STAGNATION: &str = "DefaultStagnation";
+
+        let mut values = IniValues::parse(content)?;
+        let mut unsupported = vec![];
+        let mut config = MutationConfig::default();
+
+        let population_size = values
+            .take_parsed(NEAT, "pop_size")?
+            .unwrap_or(DEFAULT_POPULATION_SIZE);
+        let mut fitness_threshold = values.take_parsed(NEAT, "fitness_threshold")?;
+        if values.take_bool(NEAT, "no_fitness_termination")? == Some(true) {
+            fitness_threshold = None;
+        }
+        values.take_expected(NEAT, "fitness_criterion", |v| v == "max", &mut unsupported);
+        values.take_expected(
+            NEAT,
+            "reset_on_extinction",
+            |v| v.eq_ignore_ascii_case("false"),
+            &mut unsupported,
+        );
+
+        let required = |key: &str, value: Option<usize>| {
+            value.ok_or_else(|| NeatPythonError::MissingKey(format!("{}.{}", GENOME, key)))
+        };
+        let input_neurons = required("num_inputs", values.take_parsed(GENOME, "num_inputs")?)?;
+        let output_neurons = required("num_outputs", values.take_parsed(GENOME, "num_outputs")?)?;
+        values.take_expected(GENOME, "num_hidden", is_zero, &mut unsupported);
+        if let Some(feed_forward) = values.take_bool(GENOME, "feed_forward")? {
+            config.feed_forward = feed_forward;
+        }
+        let mut initially_connected = true;
+        if let Some(connection) = values.take(GENOME, "initial_connection") {
+            match connection.as_str() {
+                "full" | "full_direct" | "full_nodirect" => {}
+                "unconnected" => initially_connected = false,
+                _ => unsupported.push(format!("{}.initial_connection = {}", GENOME, connection)),
+            }
+        }
+
+        values.take_expected(
+            GENOME,
+            "activation_default",
+            |v| v == "sigmoid",
+            &mut unsupported,
+        );
+        if let Some(rate) = values.take_parsed(GENOME, "activation_mutate_rate")? {
+            config.activation_mutation_rate = rate;
+        }
+        if let Some(options) = values.take(GENOME, "activation_options") {
+            let (known, unknown): (Vec<&str>, Vec<&str>) = options
+                .split_whitespace()
+                .partition(|name| activation_from_name(name).is_some());
+            if !unknown.is_empty() {
+                unsupported.push(format!(
+                    "{}.activation_options = {}",
+                    GENOME,
+                    unknown.join(" ")
+                ));
+            }
+            if !known.is_empty() {
+                config.allowed_activations = known
+                    .iter()
+                    .filter_map(|name| activation_from_name(name))
+                    .collect();
+            }
+        }
+        values.take_expected(
+            GENOME,
+            "aggregation_default",
+            |v| v == "sum",
+            &mut unsupported,
+        );
+        values.take_expected(
+            GENOME,
+            "aggregation_options",
+            |v| v == "sum",
+            &mut unsupported,
+        );
+        values.take_expected(GENOME, "aggregation_mutate_rate", is_zero, &mut unsupported);
+
+        for (prefix, init_range, mutate_power) in &mut [
+            (
+                "bias",
+                &mut config.bias_init_range,
+                &mut config.bias_mutate_power,
+            ),
+            (
+                "weight",
+                &mut config.weight_init_range,
+                &mut config.weight_mutate_power,
+            ),
+        ] {
+            let key = |name: &str| format!("{}_{}", prefix, name);
+            values.take_expected(GENOME, &key("init_mean"), is_zero, &mut unsupported);
+            let init_type = values.take(GENOME, &key("init_type"));
+            if let Some(stdev) = values.take_parsed::<f64>(GENOME, &key("init_stdev"))? {
+                **init_range = match init_type.as_deref() {
+                    // neat-python draws uniform values within two deviations
+                    Some("uniform") => 2f64 * stdev,
+                    _ => uniform_range(stdev),
+                };
+            }
+            if let Some(power) = values.take_parsed::<f64>(GENOME, &key("mutate_power"))? {
+                **mutate_power = uniform_range(power);
+            }
+            for bound in &["max_value", "min_value"] {
+                if let Some(value) = values.take(GENOME, &key(bound)) {
+                    unsupported.push(format!("{}.{} = {}", GENOME, key(bound), value));
+                }
+            }
+        }
+        let mut rates = |prefix: &str| -> Result<Option<(f64, f64)>, NeatPythonError> {
+            let mutate = values.take_parsed::<f64>(GENOME, &format!("{}_mutate_rate", prefix))?;
+            let replace = values.take_parsed::<f64>(GENOME, &format!("{}_replace_rate", prefix))?;
+            if mutate.is_none() && replace.is_none() {
+                return Ok(None);
+            }
+            let (mutate, replace) = (mutate.unwrap_or(0f64), replace.unwrap_or(0f64));
+            let rate = (mutate + replace).min(1f64);
+            let replace_share = if mutate + replace > 0f64 {
+                replace / (mutate + replace)
+            } else {
+                0f64
+            };
+            Ok(Some((rate, replace_share)))
+        };
+        if let Some((rate, replace_share)) = rates("bias")? {
+            config.bias_mutation_rate = rate;
+            config.bias_replace_rate = replace_share;
+        }
+        if let Some((rate, replace_share)) = rates("weight")? {
+            config.weight_mutation_rate = rate;
+            config.weight_perturbation_rate = 1f64 - replace_share;
+        }
+
+        // Responses are folded into the weights, they must stay at 1
+        values.take_expected(
+            GENOME,
+            "response_init_mean",
+            |v| v == "1.0" || v == "1",
+            &mut unsupported,
+        );
+        for key in &[
+            "response_init_stdev",
+            "response_mutate_rate",
+            "response_replace_rate",
+        ] {
+            values.take_expected(GENOME, key, is_zero, &mut unsupported);
+        }
+        for key in &[
+            "response_init_type",
+            "response_mutate_power",
+            "response_max_value",
+            "response_min_value",
+        ] {
+            values.take(GENOME, key);
+        }
+
+        for (key, rate) in &mut [
+            ("conn_add_prob", &mut config.add_connection_rate),
+            ("conn_delete_prob", &mut config.delete_connection_rate),
+            ("node_add_prob", &mut config.add_neuron_rate),
+            ("node_delete_prob", &mut config.delete_neuron_rate),
+            ("enabled_mutate_rate", &mut config.toggle_expression_rate),
+        ] {
+            if let Some(value) = values.take_parsed(GENOME, key)? {
+                **rate = value;
+            }
+        }
+        values.take_expected(
+            GENOME,
+            "enabled_default",
+            |v| v.eq_ignore_ascii_case("true"),
+            &mut unsupported,
+        );
+        values.take_expected(
+            GENOME,
+            "single_structural_mutation",
+            |v| v.eq_ignore_ascii_case("false"),
+            &mut unsupported,
+        );
+        values.take_expected(
+            GENOME,
+            "structural_mutation_surer",
+            |v| v == "default" || v.eq_ignore_ascii_case("false"),
+            &mut unsupported,
+        );
+
+        // neat-python makes no difference between excess and disjoint genes
+        if let Some(coefficient) =
+            values.take_parsed(GENOME, "compatibility_disjoint_coefficient")?
+        {
+            config.compatibility_excess_coefficient = coefficient;
+            config.compatibility_disjoint_coefficient = coefficient;
+        }
+        // and weighs the node distance like the connection distance
+        if let Some(coefficient) = values.take_parsed(GENOME, "compatibility_weight_coefficient")? {
+            config.compatibility_weight_coefficient = coefficient;
+            config.compatibility_neuron_difference_coefficient = coefficient;
+        }
+        if let Some(threshold) = values.take_parsed(SPECIES, "compatibility_threshold")? {
+            config.compatibility_threshold = threshold;
+        }
+
+        values.take_expected(
+            STAGNATION,
+            "species_fitness_func",
+            |v| v == "max",
+            &mut unsupported,
+        );
+        if let Some(generations) = values.take_parsed(STAGNATION, "max_stagnation")? {
+            config.max_stagnation = generations;
+        }
+        if let Some(species) = values.take_parsed(STAGNATION, "species_elitism")? {
+            config.species_elitism = species;
+        }
+
+        for ((section, key), value) in values.values {
+            unsupported.push(format!("{}.{} = {}", section, key, value));
+        }
+        Ok(NeatPythonConfig {
+            mutation_config: config,
+            population_size,
+            fitness_threshold,
+            input_neurons,
+            output_neurons,
+            initially_connected,
+            unsupported,
+        })
+    }
+
+    /// Population of `population_size` genomes with the configured inputs,
+    /// outputs and initial connections
+    pub fn create_population(&self) -> Population {
+        if self.initially_connected {
+            Population::create_population_initialized_with_config(
+                self.population_size,
+                self.input_neurons,
+                self.output_neurons,
+                self.mutation_config.clone(),
+            )
+        } else {
+            Population::create_population_unconnected_with_config(
+                self.population_size,
+                self.input_neurons,
+                self.output_neurons,
+                self.mutation_config.clone(),
+            )
+        }
+    }
+}
+
+/// Node gene of a neat-python `DefaultGenome`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NeatPythonNode {
+    /// Node key: outputs are numbered from 0, hidden nodes after them
+    pub key: i64,
+    /// Bias added to the aggregated inputs
+    pub bias: f64,
+    /// Factor of the aggregated inputs
+    pub response: f64,
+    /// Name of the activation function
+    pub activation: String,
+    /// Name of the aggregation function
+    pub aggregation: String,
+}
+
+/// Connection gene of a neat-python `DefaultGenome`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NeatPythonConnection {
+    /// (input node, output node), inputs are numbered -1, -2, ...
+    pub key: (i64, i64),
+    /// Weight of the connection
+    pub weight: f64,
+    /// Whether the connection is expressed
+    pub enabled: bool,
+}
+
+/// Genome in the layout of neat-python's `DefaultGenome`.
+///
+/// With the `serde` feature it reads and writes JSON that can be produced from
+/// a neat-python genome `g` with:
+///
+/// ```python
+/// json.dump({
+///     "key": g.key, "fitness": g.fitness,
+///     "nodes": [{"key": k, "bias": n.bias, "response": n.response,
+///                "activation": n.activation, "aggregation": n.aggregation}
+///               for k, n in g.nodes.items()],
+///     "connections": [{"key": list(k), "weight": c.weight, "enabled": c.enabled}
+///                     for k, c in g.connections.items()],
+/// }, file)
+/// ```
+///
+/// neat-python scales the argument of its activation functions: sigmoid and
+/// sin by 5, tanh by 2.5 and gauss by √5 (`exp(-5z²)`). This gain and the
+/// response are folded into the bias of the neuron and the weights of its
+/// incoming connections on import, and divided out on export. neat-python also
+/// clamps the scaled argument of sin to ±60 and of gauss to ±3.4, beyond which
+/// the networks differ. Imported input neurons use the identity function, since
+/// neat-python reads inputs as they are.
+///
+/// `Organism::activate` returns the weighted sum reaching each output neuron;
+/// neat-python's output is the activation function of the output neuron applied
+/// to that sum plus its bias. Time constants, input biases and input activation
+/// functions have no neat-python equivalent and are lost on export.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NeatPythonGenome {
+    /// Genome key
+    pub key: i64,
+    /// Fitness, if the genome was evaluated
+    pub fitness: Option<f64>,
+    /// Output and hidden nodes
+    pub nodes: Vec<NeatPythonNode>,
+    /// Connections keyed by node pairs
+    pub connections: Vec<NeatPythonConnection>,
+}
+
+impl NeatPythonGenome {
+    /// Genome in neat-python's layout, inputs and outputs keep their order and
+    /// hidden neurons are numbered after the outputs by increasing id. Gains of
+    /// the activation functions are divided out of biases and weights.
+    pub fn from_genome(genome: &Genome) -> Result<NeatPythonGenome, NeatPythonError> {
+        if !genome.has_io_roles() {
+            return Err(NeatPythonError::MissingRoles);
+        }
+        let mut keys: HashMap<usize, i64> = HashMap::new();
+        for (position, id) in genome
+            .neuron_ids_with_role(NeuronRole::Input)
+            .iter()
+            .enumerate()
+        {
+            keys.insert(*id, -(position as i64) - 1);
+        }
+        let mut next_key = 0;
+        for role in &[NeuronRole::Output, NeuronRole::Hidden] {
+            for id in genome.neuron_ids_with_role(*role) {
+                keys.insert(id, next_key);
+                next_key += 1;
+            }
+        }
+
+        let mut nodes = vec![];
+        for node in genome.get_nodes() {
+            if node.role() == NeuronRole::Input {
+                continue;
+            }
+            nodes.push(NeatPythonNode {
+                key: keys[&node.id()],
+                bias: node.bias() / activation_gain(node.activation()),
+                response: 1f64,
+                activation: activation_name(node.activation())
+                    .ok_or_else(|| {
+                        NeatPythonError::UnsupportedFunction(format!("{:?}", node.activation()))
+                    })?
+                    .to_string(),
+                aggregation: "sum".to_string(),
+            });
+        }
+        nodes.sort_by_key(|node| node.key);
+        let gain_of = |id| {
+            genome
+                .get_node(id)
+                .map_or(1f64, |node| activation_gain(node.activation()))
+        };
+        let connections = genome
+            .get_genes()
+            .iter()
+            .map(|gene| NeatPythonConnection {
+                key: (keys[&gene.in_neuron_id()], keys[&gene.out_neuron_id()]),
+                weight: gene.weight() / gain_of(gene.out_neuron_id()),
+                enabled: gene.enabled(),
+            })
+            .collect();
+        Ok(NeatPythonGenome {
+            key: 0,
+            fitness: None,
+            nodes,
+            connections,
+        })
+    }
+
+    /// Genome computing the network of neat-python, see the type documentation:
+    /// input `-k` becomes neuron `k - 1`, output `k` neuron `input_neurons + k`
+    /// and hidden nodes follow by increasing key
+    pub fn to_genome(
+        &self,
+        input_neurons: usize,
+        output_neurons: usize,
+    ) -> Result<Genome, NeatPythonError> {
+        let mut genome = Genome::new_unconnected(input_neurons, output_neurons);
+        for id in 0..input_neurons {
+            let mut input = *genome.get_node(id).unwrap();
+            input.set_activation(Activation::Identity);
+            genome.add_node(input);
+        }
+        let mut hidden_keys = self
+            .nodes
+            .iter()
+            .map(|node| node.key)
+            .filter(|key| *key >= output_neurons as i64)
+            .collect::<Vec<i64>>();
+        hidden_keys.sort_unstable();
+        let id_of = |key: i64| -> Result<usize, NeatPythonError> {
+            if key < 0 && -key <= input_neurons as i64 {
+                Ok((-key - 1) as usize)
+            } else if key >= 0 && key < output_neurons as i64 {
+                Ok(input_neurons + key as usize)
+            } else {
+                hidden_keys
+                    .binary_search(&key)
+                    .map(|rank| input_neurons + output_neurons + rank)
+                    .map_err(|_| NeatPythonError::UnknownNode(key))
+            }
+        };
+
+        let mut factors = HashMap::new();
+        for node in &self.nodes {
+            if node.aggregation != "sum" {
+                return Err(NeatPythonError::UnsupportedFunction(
+                    node.aggregation.clone(),
+                ));
+            }
+            let activation = activation_from_name(&node.activation)
+                .ok_or_else(|| NeatPythonError::UnsupportedFunction(node.activation.clone()))?;
+            let id = id_of(node.key)?;
+            let role = if id < input_neurons + output_neurons {
+                NeuronRole::Output
+            } else {
+                NeuronRole::Hidden
+            };
+            let gain = activation_gain(activation);
+            let mut node_gene = NodeGene::new(id, role);
+            node_gene.set_bias(node.bias * gain);
+            node_gene.set_activation(activation);
+            genome.add_node(node_gene);
+            factors.insert(id, node.response * gain);
+        }
+        for connection in &self.connections {
+            let (in_neuron_id, out_neuron_id) =
+                (id_of(connection.key.0)?, id_of(connection.key.1)?);
+            let factor = factors.get(&out_neuron_id).cloned().unwrap_or(1f64);
+            genome.try_add_gene(Gene::new(
+                in_neuron_id,
+                out_neuron_id,
+                connection.weight * factor,
+                connection.enabled,
+            ))?;
+        }
+        genome.validate()?;
+        Ok(genome)
+    }
+
+    /// Read a genome from JSON
+    #[cfg(feature = "serde")]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<NeatPythonGenome, NeatPythonError> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content).map_err(io::Error::from)?)
+    }
+
+    /// Write the genome as JSON
+    #[cfg(feature = "serde")]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), NeatPythonError> {
+        let content = serde_json::to_string_pretty(self).map_err(io::Error::from)?;
+        Ok(fs::write(path, content)?)
+    }
+}
+
+fn activation_from_name(name: &str) -> Option<Activation> {
+    match name {
+        "sigmoid" => Some(Activation::Sigmoid),
+        "tanh" => Some(Activation::Tanh),
+        "relu" => Some(Activation::Relu),
+        "gauss" => Some(Activation::Gaussian),
+        "sin" => Some(Activation::Sine),
+        "identity" => Some(Activation::Identity),
+        "abs" => Some(Activation::Abs),
+        _ => None,
+    }
+}
+
+/// Factor neat-python applies to the argument of its version of the function
+fn activation_gain(activation: Activation) -> f64 {
+    match activation {
+        Activation::Sigmoid | Activation::Sine => 5f64,
+        Activation::Tanh => 2.5,
+        Activation::Gaussian => 5f64.sqrt(),
+        _ => 1f64,
+    }
+}
+
+fn activation_name(activation: Activation) -> Option<&'static str> {
+    Activation::ALL
+        .iter()
+        .zip(&[
+            "sigmoid", "tanh", "relu", "gauss", "sin", "", "identity", "abs",
+        ])
+        .find(|(candidate, _)| **candidate == activation)
+        .map(|(_, name)| *name)
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::organism::Organism;
+
+    const CONFIG: &str = "
+# neat-python XOR example
+[NEAT]
+fitness_criterion     = max
+fitness_threshold     = 3.9
+pop_size              = 150
+reset_on_extinction   = False
+
+[DefaultGenome]
+# node activation options
+activation_default      = sigmoid
+activation_mutate_rate  = 0.1
+activation_options      = sigmoid tanh
+                          cube
+aggregation_default     = sum
+aggregation_mutate_rate = 0.0
+aggregation_options     = sum
+bias_init_mean          = 0.0
+bias_init_stdev         = 1.0
+bias_max_value          = 30.0
+bias_min_value          = -30.0
+bias_mutate_power       = 0.5
+bias_mutate_rate        = 0.7
+bias_replace_rate       = 0.1
+compatibility_disjoint_coefficient = 1.0
+compatibility_weight_coefficient   = 0.5
+conn_add_prob           = 0.5
+conn_delete_prob        = 0.5
+enabled_default         = True
+enabled_mutate_rate     = 0.01
+feed_forward            = True
+initial_connection      = full
+node_add_prob           = 0.2
+node_delete_prob        = 0.2
+num_hidden              = 0
+num_inputs              = 2
+num_outputs             = 1
+response_init_mean      = 1.0
+response_init_stdev     = 0.0
+response_max_value      = 30.0
+response_min_value      = -30.0
+response_mutate_power   = 0.0
+response_mutate_rate    = 0.0
+response_replace_rate   = 0.0
+weight_init_mean        = 0.0
+weight_init_stdev       = 1.0
+weight_max_value        = 30
+weight_min_value        = -30
+weight_mutate_power     = 0.5
+weight_mutate_rate      = 0.8
+weight_replace_rate     = 0.1
+
+[DefaultSpeciesSet]
+compatibility_threshold = 3.0
+
+[DefaultStagnation]
+species_fitness_func = max
+max_stagnation       = 20
+species_elitism      = 2
+
+[DefaultReproduction]
+elitism            = 2
+survival_threshold = 0.2
+";
+
+    #[test]
+    fn config_should_map_onto_mutation_config() {
+        let config = NeatPythonConfig::parse(CONFIG).unwrap();
+        let mutation = &config.mutation_config;
+        assert_eq!(config.population_size, 150);
+        assert_eq!(config.fitness_threshold, Some(3.9));
+        assert_eq!((config.input_neurons, config.output_neurons), (2, 1));
+        assert!(config.initially_connected);
+        assert!(mutation.feed_forward);
+        assert_eq!(
+            mutation.allowed_activations,
+            vec![Activation::Sigmoid, Activation::Tanh]
+        );
+        assert!((mutation.bias_mutation_rate - 0.8).abs() < 1e-12);
+        assert!((mutation.bias_replace_rate - 0.125).abs() < 1e-12);
+        assert!((mutation.weight_mutation_rate - 0.9).abs() < 1e-12);
+        assert!((mutation.weight_perturbation_rate - 0.8 / 0.9).abs() < 1e-12);
+        assert!((mutation.weight_init_range - 3f64.sqrt()).abs() < 1e-12);
+        assert!((mutation.compatibility_threshold - 3f64).abs() < f64::EPSILON);
+        assert!((mutation.compatibility_neuron_difference_coefficient - 0.5).abs() < f64::EPSILON);
+        assert!((mutation.delete_neuron_rate - 0.2).abs() < f64::EPSILON);
+        assert_eq!(mutation.max_stagnation, 20);
+        assert_eq!(
+            config.unsupported,
+            vec![
+                "DefaultGenome.activation_options = cube",
+                "DefaultGenome.bias_max_value = 30.0",
+                "DefaultGenome.bias_min_value = -30.0",
+                "DefaultGenome.weight_max_value = 30",
+                "DefaultGenome.weight_min_value = -30",
+                "DefaultReproduction.elitism = 2",
+                "DefaultReproduction.survival_threshold = 0.2",
+            ]
+        );
+        assert_eq!(config.create_population().size(), 150);
+    }
+
+    #[test]
+    fn config_errors_should_point_at_the_problem() {
+        match NeatPythonConfig::parse("[NEAT]\npop_size = many\n") {
+            Err(NeatPythonError::InvalidValue { key, value }) => {
+                assert_eq!((key.as_str(), value.as_str()), ("NEAT.pop_size", "many"))
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+        match NeatPythonConfig::parse("[NEAT]\npop_size\n") {
+            Err(NeatPythonError::Syntax { line: 2, .. }) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+        match NeatPythonConfig::parse("[DefaultGenome]\nnum_inputs = 2\n") {
+            Err(NeatPythonError::MissingKey(key)) => assert_eq!(key, "DefaultGenome.num_outputs"),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    fn neat_python_genome() -> NeatPythonGenome {
+        let node = |key, bias, response, activation: &str| NeatPythonNode {
+            key,
+            bias,
+            response,
+            activation: activation.to_string(),
+            aggregation: "sum".to_string(),
+        };
+        let connection = |key, weight, enabled| NeatPythonConnection {
+            key,
+            weight,
+            enabled,
+        };
+        NeatPythonGenome {
+            key: 7,
+            fitness: Some(3.5),
+            nodes: vec![node(0, 0.5, 1f64, "sigmoid"), node(12, -0.25, 2f64, "tanh")],
+            connections: vec![
+                connection((-1, 12), 1.5, true),
+                connection((-2, 0), -0.5, false),
+                connection((12, 0), 0.75, true),
+            ],
+        }
+    }
+
+    #[test]
+    fn neat_python_genomes_should_convert_to_genomes() {
+        let genome = neat_python_genome().to_genome(2, 1).unwrap();
+        assert_eq!(genome.input_neurons(), 2);
+        assert_eq!(genome.neuron_ids_with_role(NeuronRole::Hidden), vec![3]);
+        let hidden = genome.get_node(3).unwrap();
+        assert_eq!(hidden.activation(), Activation::Tanh);
+        assert!((hidden.bias() + 0.625).abs() < f64::EPSILON);
+        // The response and gain of the hidden node are folded into its incoming weight
+        let gene = |in_neuron_id, out_neuron_id| {
+            genome
+                .get_genes()
+                .iter()
+                .find(|gene| {
+                    gene.in_neuron_id() == in_neuron_id && gene.out_neuron_id() == out_neuron_id
+                })
+                .unwrap()
+        };
+        assert!((gene(0, 3).weight() - 7.5).abs() < f64::EPSILON);
+        assert!(!gene(1, 2).enabled());
+        assert!((genome.get_node(2).unwrap().bias() - 2.5).abs() < f64::EPSILON);
+        assert_eq!(
+            genome.get_node(0).unwrap().activation(),
+            Activation::Identity
+        );
+
+        let exported = NeatPythonGenome::from_genome(&genome).unwrap();
+        assert_eq!(exported.nodes[1].key, 1);
+        assert_eq!(exported.connections[0].key, (-1, 1));
+        assert!((exported.nodes[0].bias - 0.5).abs() < 1e-12);
+        assert!((exported.connections[2].weight - 0.75).abs() < 1e-12);
+        assert!(exported.to_genome(2, 1).unwrap().diff(&genome).is_empty());
+    }
+
+    #[test]
+    fn imported_networks_should_compute_like_neat_python() {
+        // neat-python's activation functions
+        let sigmoid = |z: f64| 1f64 / (1f64 + (-(5f64 * z).clamp(-60f64, 60f64)).exp());
+        let tanh = |z: f64| (2.5 * z).clamp(-60f64, 60f64).tanh();
+        let gauss = |z: f64| (-5f64 * z.clamp(-3.4, 3.4).powi(2)).exp();
+
+        let mut neat_python = neat_python_genome();
+        neat_python.nodes.push(NeatPythonNode {
+            key: 13,
+            bias: 0.1,
+            response: 0.5,
+            activation: "gauss".to_string(),
+            aggregation: "sum".to_string(),
+        });
+        neat_python.connections.push(NeatPythonConnection {
+            key: (-2, 13),
+            weight: -0.8,
+            enabled: true,
+        });
+        neat_python.connections.push(NeatPythonConnection {
+            key: (13, 0),
+            weight: 1.25,
+            enabled: true,
+        });
+        let mut organism = Organism::new(neat_python.to_genome(2, 1).unwrap());
+        organism.feed_forward = true;
+        let output_node = *organism.genome.get_node(2).unwrap();
+
+        for inputs in &[[0f64, 0f64], [0.3, -0.7], [-1f64, 2f64]] {
+            let hidden12 = tanh(-0.25 + 2f64 * (1.5 * inputs[0]));
+            let hidden13 = gauss(0.1 + 0.5 * (-0.8 * inputs[1]));
+            let expected = sigmoid(0.5 + 0.75 * hidden12 + 1.25 * hidden13);
+
+            let mut output = [0f64];
+            organism.activate(inputs.to_vec(), &mut output);
+            let found = output_node
+                .activation()
+                .activate(output[0] + output_node.bias());
+            assert!(
+                (found - expected).abs() < 1e-12,
+                "{} != {}",
+                found,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_nodes_and_functions_should_be_rejected() {
+        let mut unknown_node = neat_python_genome();
+        unknown_node.connections[0].key = (-3, 12);
+        match unknown_node.to_genome(2, 1) {
+            Err(NeatPythonError::UnknownNode(-3)) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+        let mut unknown_function = neat_python_genome();
+        unknown_function.nodes[0].activation = "cube".to_string();
+        match unknown_function.to_genome(2, 1) {
+            Err(NeatPythonError::UnsupportedFunction(name)) => assert_eq!(name, "cube"),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn neat_python_genomes_should_round_trip_as_json() {
+        let genome = neat_python_genome();
+        let path =
+            std::env::temp_dir().join(format!("rustneat-neat-python-{}", std::process::id()));
+        genome.save(&path).unwrap();
+        let loaded = NeatPythonGenome::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, genome);
+    }
+}
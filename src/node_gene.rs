@@ -0,0 +1,97 @@
+use crate::activation::Activation;
+use crate::mutation_config::MutationConfig;
+use crate::rng;
+
+/// Role of a neuron in the network
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NeuronRole {
+    /// Receives a sensor value
+    Input,
+    /// Its state is read as a network output
+    Output,
+    /// Created by structural mutations
+    Hidden,
+}
+
+/// A neuron Gene
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeGene {
+    id: usize,
+    role: NeuronRole,
+    bias: f64,
+    tau: f64,
+    activation: Activation,
+    #[cfg_attr(feature = "serde", serde(default))]
+    frozen: bool,
+}
+
+/// CTRNN time constant τ of new neurons. Small τ = feedforward (instant
+/// response), large τ = temporal memory (slow response).
+pub(crate) const DEFAULT_TAU: f64 = 0.01;
+
+impl NodeGene {
+    /// Create a new neuron gene without bias
+    pub fn new(id: usize, role: NeuronRole) -> NodeGene {
+        NodeGene {
+            id,
+            role,
+            bias: 0f64,
+            tau: DEFAULT_TAU,
+            activation: Activation::default(),
+            frozen: false,
+        }
+    }
+    /// Random time constant within `tau ± tau_init_range` of the config, never
+    /// below `tau_min`
+    pub fn generate_tau(config: &MutationConfig) -> f64 {
+        let offset = (rng::random::<f64>() * 2f64 - 1f64) * config.tau_init_range;
+        (config.tau + offset).max(config.tau_min)
+    }
+    /// Random bias in [-bias_init_range, bias_init_range] of the config
+    pub fn generate_bias(config: &MutationConfig) -> f64 {
+        (rng::random::<f64>() * 2f64 - 1f64) * config.bias_init_range
+    }
+    /// Neuron id
+    pub fn id(&self) -> usize {
+        self.id
+    }
+    /// Role of the neuron
+    pub fn role(&self) -> NeuronRole {
+        self.role
+    }
+    /// Bias θ of the neuron
+    pub fn bias(&self) -> f64 {
+        self.bias
+    }
+    /// Set the bias of the neuron
+    pub fn set_bias(&mut self, bias: f64) {
+        self.bias = bias;
+    }
+    /// CTRNN time constant τ of the neuron
+    pub fn tau(&self) -> f64 {
+        self.tau
+    }
+    /// Set the time constant of the neuron
+    pub fn set_tau(&mut self, tau: f64) {
+        self.tau = tau;
+    }
+    /// Activation function of the neuron
+    pub fn activation(&self) -> Activation {
+        self.activation
+    }
+    /// Set the activation function of the neuron
+    pub fn set_activation(&mut self, activation: Activation) {
+        self.activation = activation;
+    }
+    /// Frozen neurons keep their bias, time constant and activation, and are never
+    /// deleted
+    pub fn frozen(&self) -> bool {
+        self.frozen
+    }
+    /// Freeze or unfreeze the neuron
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen;
+    }
+}
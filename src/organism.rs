@@ -1,12 +1,31 @@
+use crate::activation::Activation;
+use crate::crossover::{Crossover, CrossoverOperator};
 use crate::ctrnn::{Ctrnn, CtrnnNeuralNetwork};
+use crate::gene::Gene;
 use crate::genome::Genome;
+use crate::innovation::InnovationTracker;
+use crate::mutation::MutationRegistry;
+use crate::mutation_config::MutationConfig;
+use crate::node_gene::{NeuronRole, DEFAULT_TAU};
 use std::cmp;
 use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Position of the neurons in the CTRNN vectors of an activation
+pub(crate) struct CtrnnLayout {
+    /// Position of each neuron id
+    pub(crate) indexes: HashMap<usize, usize>,
+    /// Sensors fed to the first positions
+    pub(crate) sensors_len: usize,
+    /// Positions read as outputs, in order
+    pub(crate) output_indexes: Vec<usize>,
+}
 
 /// An organism is a Genome with fitness.
 /// Also maitain a fitenss measure of the organism
 #[allow(missing_docs)]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Organism {
     pub genome: Genome,
     pub fitness: f64,
@@ -15,13 +34,17 @@ pub struct Organism {
     /// If true, skip evaluation and preserve current fitness (used for elitism)
     pub preserve_fitness: bool,
     /// Persistent CTRNN state across activate() calls within an episode
+    #[cfg_attr(feature = "serde", serde(skip))]
     ctrnn_state: Vec<f64>,
-    /// CTRNN neuron time constant τ (default 0.01).
-    /// Small τ = feedforward (instant response), large τ = temporal memory (slow response).
-    pub tau: f64,
+    /// Neuron id at each position of `ctrnn_state`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    ctrnn_neuron_ids: Vec<usize>,
     /// Simulated time per activate() call in seconds (default 0.1).
     /// Number of Euler steps = step_time / dt where dt=0.01.
     pub step_time: f64,
+    /// Evaluate acyclic networks once in topological order, giving the state the
+    /// CTRNN would converge to. Networks with cycles are still simulated.
+    pub feed_forward: bool,
 }
 
 impl Ord for Organism {
@@ -48,13 +71,14 @@ impl Organism {
     /// Create a new organmism form a single genome.
     pub fn new(genome: Genome) -> Organism {
         Organism {
-            genome: genome,
+            genome,
             fitness: 0f64,
             adjusted_fitness: 0f64,
             preserve_fitness: false,
             ctrnn_state: vec![],
-            tau: 0.01,
+            ctrnn_neuron_ids: vec![],
             step_time: 0.1,
+            feed_forward: false,
         }
     }
     /// Return a new Orgnaism by mutating this Genome and fitness of zero
@@ -62,27 +86,71 @@ impl Organism {
         let mut new_genome = self.genome.clone();
         new_genome.mutate();
         let mut child = Organism::new(new_genome);
-        child.tau = self.tau;
         child.step_time = self.step_time;
+        child.feed_forward = self.feed_forward;
         child
     }
     /// Return a new Organism by mutating with specific config
-    pub fn mutate_with_config(&self, config: &crate::mutation_config::MutationConfig) -> Organism {
+    pub fn mutate_with_config(&self, config: &MutationConfig) -> Organism {
         let mut new_genome = self.genome.clone();
         new_genome.mutate_with_config(config);
         let mut child = Organism::new(new_genome);
-        child.tau = self.tau;
         child.step_time = self.step_time;
+        child.feed_forward = self.feed_forward;
+        child
+    }
+    /// Return a new Organism by mutating with specific config, numbering new
+    /// structure with the population's innovation tracker
+    pub fn mutate_with_tracker(
+        &self,
+        config: &MutationConfig,
+        tracker: &mut InnovationTracker,
+    ) -> Organism {
+        self.mutate_with_registry(config, tracker, &MutationRegistry::new())
+    }
+    /// Return a new Organism mutated with the built-in mutations of `config` and
+    /// the operators of `registry`
+    pub fn mutate_with_registry(
+        &self,
+        config: &MutationConfig,
+        tracker: &mut InnovationTracker,
+        registry: &MutationRegistry,
+    ) -> Organism {
+        let mut new_genome = self.genome.clone();
+        new_genome.mutate_with_registry(config, tracker, registry);
+        let mut child = Organism::new(new_genome);
+        child.step_time = self.step_time;
+        child.feed_forward = self.feed_forward;
         child
     }
     /// Mate this organism with another
     pub fn mate(&self, other: &Organism) -> Organism {
-        let mut child = Organism::new(
-            self.genome
-                .mate(&other.genome, self.fitness < other.fitness),
-        );
-        child.tau = self.tau;
+        self.mate_with_config(other, &MutationConfig::default())
+    }
+    /// Mate this organism with another through a crossover operator picked from
+    /// `config`
+    pub fn mate_with_config(&self, other: &Organism, config: &MutationConfig) -> Organism {
+        let operator = CrossoverOperator::choose(&config.crossover_operators)
+            .unwrap_or(CrossoverOperator::Uniform);
+        self.mate_with(&[other], &operator, config)
+    }
+    /// Mate this organism with `mates` through a crossover operator, the
+    /// fittest parent first
+    pub fn mate_with(
+        &self,
+        mates: &[&Organism],
+        crossover: &dyn Crossover,
+        config: &MutationConfig,
+    ) -> Organism {
+        let mut parents = vec![self];
+        parents.extend_from_slice(mates);
+        parents.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap_or(Ordering::Equal));
+        let genomes: Vec<&Genome> = parents.iter().map(|parent| &parent.genome).collect();
+        let mut genome = crossover.crossover(&genomes, config);
+        genome.enforce_recurrence_policy(config.effective_recurrence_policy());
+        let mut child = Organism::new(genome);
         child.step_time = self.step_time;
+        child.feed_forward = self.feed_forward;
         child
     }
     /// Reset the internal CTRNN state (call at the start of each episode)
@@ -90,23 +158,53 @@ impl Organism {
         self.ctrnn_state = vec![];
     }
 
-    /// Activate this organism in the NN
-    pub fn activate(&mut self, sensors: Vec<f64>, outputs: &mut Vec<f64>) {
-        let neurons_len = self.genome.len();
-        let sensors_len = sensors.len();
+    /// State of each neuron after the last `activate` call, by neuron id. Empty
+    /// before the first call and after `reset_state`.
+    pub fn neuron_states(&self) -> HashMap<usize, f64> {
+        self.ctrnn_neuron_ids
+            .iter()
+            .cloned()
+            .zip(self.ctrnn_state.iter().cloned())
+            .collect()
+    }
+
+    /// Activate this organism in the NN.
+    /// Sensors feed the input neurons and outputs are read from the output neurons
+    /// of the genome. Genomes without roles use the first `sensors.len()` neurons as
+    /// inputs and the next ones as outputs.
+    pub fn activate(&mut self, sensors: Vec<f64>, outputs: &mut [f64]) {
+        let CtrnnLayout {
+            indexes,
+            sensors_len,
+            output_indexes,
+        } = self.ctrnn_layout(sensors.len(), outputs.len());
+        let neurons_len = indexes.len();
+        self.ctrnn_neuron_ids = vec![0; neurons_len];
+        for (neuron_id, index) in &indexes {
+            self.ctrnn_neuron_ids[*index] = *neuron_id;
+        }
 
-        let tau = vec![self.tau; neurons_len];
-        let theta = self.get_bias();
+        let tau = self.get_taus(&indexes);
+        let theta = self.get_bias(&indexes);
+        let neuron_activations = self.get_activations(&indexes);
 
-        let mut i = sensors.clone();
+        let mut i = sensors;
+        i.truncate(cmp::min(sensors_len, neurons_len));
+        i.resize(neurons_len, 0.0);
 
-        if neurons_len < sensors_len {
-            i.truncate(neurons_len);
-        } else {
-            i = [i, vec![0.0; neurons_len - sensors_len]].concat();
+        if self.feed_forward {
+            if let Some(order) = self.genome.topological_order() {
+                let state =
+                    self.activate_feed_forward(&indexes, &order, &i, &theta, &neuron_activations);
+                for (output, index) in outputs.iter_mut().zip(output_indexes) {
+                    *output = state[index];
+                }
+                self.ctrnn_state = state;
+                return;
+            }
         }
 
-        let wji = self.get_weights();
+        let wji = self.get_weights(&indexes);
 
         // Initialize state if needed (first call or after reset_state())
         if self.ctrnn_state.len() != neurons_len {
@@ -122,65 +220,156 @@ impl Organism {
                 wji: &wji,
                 theta: &theta,
                 i: &i,
+                activations: &neuron_activations,
             },
         );
 
+        for (output, index) in outputs.iter_mut().zip(output_indexes) {
+            *output = activations[index];
+        }
+
         // Persist state for next activate() call
-        self.ctrnn_state = activations.clone();
+        self.ctrnn_state = activations;
+    }
 
-        if sensors_len < neurons_len {
-            let outputs_activations = activations.split_at(sensors_len).1.to_vec();
+    /// Position of every neuron in the CTRNN vectors for the given number of
+    /// sensors and outputs
+    pub(crate) fn ctrnn_layout(&self, sensors_len: usize, outputs_len: usize) -> CtrnnLayout {
+        if self.genome.has_io_roles() {
+            let indexes = self.neuron_indexes_by_role();
+            let output_indexes = self
+                .genome
+                .neuron_ids_with_role(NeuronRole::Output)
+                .iter()
+                .map(|id| indexes[id])
+                .collect::<Vec<usize>>();
+            CtrnnLayout {
+                indexes,
+                sensors_len: cmp::min(sensors_len, self.genome.input_neurons()),
+                output_indexes,
+            }
+        } else {
+            let indexes = self.neuron_indexes(sensors_len + outputs_len);
+            let output_indexes = (sensors_len..indexes.len()).collect::<Vec<usize>>();
+            CtrnnLayout {
+                indexes,
+                sensors_len,
+                output_indexes,
+            }
+        }
+    }
 
-            for n in 0..cmp::min(outputs_activations.len(), outputs.len()) {
-                outputs[n] = outputs_activations[n];
+    /// Single pass over the neurons in topological order, each neuron state being
+    /// its external input plus the weighted activations of its input neurons.
+    fn activate_feed_forward(
+        &self,
+        indexes: &HashMap<usize, usize>,
+        order: &[usize],
+        i: &[f64],
+        theta: &[f64],
+        activations: &[Activation],
+    ) -> Vec<f64> {
+        let mut incoming: HashMap<usize, Vec<&Gene>> = HashMap::new();
+        for gene in self.genome.get_genes().iter().filter(|gene| gene.enabled()) {
+            incoming.entry(gene.out_neuron_id()).or_default().push(gene);
+        }
+        let mut state = i.to_vec();
+        for neuron_id in order {
+            let index = indexes[neuron_id];
+            for gene in incoming.get(neuron_id).into_iter().flatten() {
+                let j = indexes[&gene.in_neuron_id()];
+                state[index] += gene.weight() * activations[j].activate(state[j] + theta[j]);
             }
         }
+        state
     }
 
-    fn get_weights(&self) -> Vec<f64> {
-        let neurons_len = self.genome.len();
+    /// Position of each neuron in the CTRNN vectors: input neurons first, then
+    /// output neurons, then hidden neurons.
+    fn neuron_indexes_by_role(&self) -> HashMap<usize, usize> {
+        let mut indexes = HashMap::new();
+        for role in &[NeuronRole::Input, NeuronRole::Output, NeuronRole::Hidden] {
+            for id in self.genome.neuron_ids_with_role(*role) {
+                indexes.insert(id, indexes.len());
+            }
+        }
+        indexes
+    }
+
+    /// Position of each neuron in the CTRNN vectors. Neurons below `positional_len`
+    /// (sensors and outputs) keep their id, the remaining neurons of the genome are
+    /// packed after them so sparse neuron ids don't inflate the weight matrix.
+    fn neuron_indexes(&self, positional_len: usize) -> HashMap<usize, usize> {
+        let positional_len = cmp::min(positional_len, self.genome.len());
+        let mut indexes: HashMap<usize, usize> = (0..positional_len).map(|id| (id, id)).collect();
+        for node in self.genome.get_nodes() {
+            if node.id() >= positional_len {
+                indexes.insert(node.id(), indexes.len());
+            }
+        }
+        indexes
+    }
+
+    pub(crate) fn get_weights(&self, indexes: &HashMap<usize, usize>) -> Vec<f64> {
+        let neurons_len = indexes.len();
         let mut matrix = vec![0.0; neurons_len * neurons_len];
         for gene in self.genome.get_genes() {
             if gene.enabled() {
-                matrix[(gene.out_neuron_id() * neurons_len) + gene.in_neuron_id()] = gene.weight()
+                matrix[(indexes[&gene.out_neuron_id()] * neurons_len)
+                    + indexes[&gene.in_neuron_id()]] = gene.weight()
             }
         }
         matrix
     }
 
-    fn get_bias(&self) -> Vec<f64> {
-        let neurons_len = self.genome.len();
-        let mut matrix = vec![0.0; neurons_len];
-        for gene in self.genome.get_genes() {
-            if gene.is_bias() {
-                matrix[gene.in_neuron_id()] += 1f64;
+    pub(crate) fn get_bias(&self, indexes: &HashMap<usize, usize>) -> Vec<f64> {
+        let mut matrix = vec![0.0; indexes.len()];
+        for node in self.genome.get_nodes() {
+            if let Some(index) = indexes.get(&node.id()) {
+                matrix[*index] = node.bias();
             }
         }
         matrix
     }
-}
 
-#[cfg(test)]
-use crate::gene::Gene;
+    pub(crate) fn get_taus(&self, indexes: &HashMap<usize, usize>) -> Vec<f64> {
+        let mut taus = vec![DEFAULT_TAU; indexes.len()];
+        for node in self.genome.get_nodes() {
+            if let Some(index) = indexes.get(&node.id()) {
+                taus[*index] = node.tau();
+            }
+        }
+        taus
+    }
+
+    pub(crate) fn get_activations(&self, indexes: &HashMap<usize, usize>) -> Vec<Activation> {
+        let mut activations = vec![Activation::default(); indexes.len()];
+        for node in self.genome.get_nodes() {
+            if let Some(index) = indexes.get(&node.id()) {
+                activations[*index] = node.activation();
+            }
+        }
+        activations
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::genome::Genome;
+    use crate::node_gene::NodeGene;
 
     #[test]
     fn should_propagate_signal_without_hidden_layers() {
         let mut organism = Organism::new(Genome::default());
-        organism.genome.add_gene(Gene::new(0, 1, 1f64, true, false));
+        organism.genome.add_gene(Gene::new(0, 1, 1f64, true));
         let sensors = vec![1.0];
         let mut output = vec![0f64];
         organism.activate(sensors, &mut output);
         assert!(output[0] > 0.5f64, "{:?} is not bigger than 0.9", output[0]);
 
         let mut organism = Organism::new(Genome::default());
-        organism
-            .genome
-            .add_gene(Gene::new(0, 1, -2f64, true, false));
+        organism.genome.add_gene(Gene::new(0, 1, -2f64, true));
         let sensors = vec![1f64];
         let mut output = vec![0f64];
         organism.activate(sensors, &mut output);
@@ -194,9 +383,9 @@ mod tests {
     #[test]
     fn should_propagate_signal_over_hidden_layers() {
         let mut organism = Organism::new(Genome::default());
-        organism.genome.add_gene(Gene::new(0, 1, 0f64, true, false));
-        organism.genome.add_gene(Gene::new(0, 2, 5f64, true, false));
-        organism.genome.add_gene(Gene::new(2, 1, 5f64, true, false));
+        organism.genome.add_gene(Gene::new(0, 1, 0f64, true));
+        organism.genome.add_gene(Gene::new(0, 2, 5f64, true));
+        organism.genome.add_gene(Gene::new(2, 1, 5f64, true));
         let sensors = vec![0f64];
         let mut output = vec![0f64];
         organism.activate(sensors, &mut output);
@@ -206,23 +395,17 @@ mod tests {
     #[test]
     fn should_work_with_cyclic_networks() {
         let mut organism = Organism::new(Genome::default());
-        organism.genome.add_gene(Gene::new(0, 1, 2f64, true, false));
-        organism.genome.add_gene(Gene::new(1, 2, 2f64, true, false));
-        organism.genome.add_gene(Gene::new(2, 1, 2f64, true, false));
+        organism.genome.add_gene(Gene::new(0, 1, 2f64, true));
+        organism.genome.add_gene(Gene::new(1, 2, 2f64, true));
+        organism.genome.add_gene(Gene::new(2, 1, 2f64, true));
         let mut output = vec![0f64];
         organism.activate(vec![10f64], &mut output);
         assert!(output[0] > 0.9, "{:#?} is not bigger than 0.9", output[0]);
 
         let mut organism = Organism::new(Genome::default());
-        organism
-            .genome
-            .add_gene(Gene::new(0, 1, -2f64, true, false));
-        organism
-            .genome
-            .add_gene(Gene::new(1, 2, -2f64, true, false));
-        organism
-            .genome
-            .add_gene(Gene::new(2, 1, -2f64, true, false));
+        organism.genome.add_gene(Gene::new(0, 1, -2f64, true));
+        organism.genome.add_gene(Gene::new(1, 2, -2f64, true));
+        organism.genome.add_gene(Gene::new(2, 1, -2f64, true));
         let mut output = vec![0f64];
         organism.activate(vec![1f64], &mut output);
         assert!(output[0] < 0.1, "{:?} is not smaller than 0.1", output[0]);
@@ -231,7 +414,7 @@ mod tests {
     #[test]
     fn activate_organims_sensor_without_enough_neurons_should_ignore_it() {
         let mut organism = Organism::new(Genome::default());
-        organism.genome.add_gene(Gene::new(0, 1, 1f64, true, false));
+        organism.genome.add_gene(Gene::new(0, 1, 1f64, true));
         let sensors = vec![0f64, 0f64, 0f64];
         let mut output = vec![0f64];
         organism.activate(sensors, &mut output);
@@ -240,7 +423,7 @@ mod tests {
     #[test]
     fn should_allow_multiple_output() {
         let mut organism = Organism::new(Genome::default());
-        organism.genome.add_gene(Gene::new(0, 1, 1f64, true, false));
+        organism.genome.add_gene(Gene::new(0, 1, 1f64, true));
         let sensors = vec![0f64];
         let mut output = vec![0f64, 0f64];
         organism.activate(sensors, &mut output);
@@ -249,29 +432,184 @@ mod tests {
     #[test]
     fn should_be_able_to_get_matrix_representation_of_the_neuron_connections() {
         let mut organism = Organism::new(Genome::default());
-        organism.genome.add_gene(Gene::new(0, 1, 1f64, true, false));
-        organism
-            .genome
-            .add_gene(Gene::new(1, 2, 0.5f64, true, false));
-        organism
-            .genome
-            .add_gene(Gene::new(2, 1, 0.5f64, true, false));
-        organism
-            .genome
-            .add_gene(Gene::new(2, 2, 0.75f64, true, false));
-        organism.genome.add_gene(Gene::new(1, 0, 1f64, true, false));
+        organism.genome.add_gene(Gene::new(0, 1, 1f64, true));
+        organism.genome.add_gene(Gene::new(1, 2, 0.5f64, true));
+        organism.genome.add_gene(Gene::new(2, 1, 0.5f64, true));
+        organism.genome.add_gene(Gene::new(2, 2, 0.75f64, true));
+        organism.genome.add_gene(Gene::new(1, 0, 1f64, true));
         assert_eq!(
-            organism.get_weights(),
+            organism.get_weights(&organism.neuron_indexes(0)),
             vec![0.0, 1.0, 0.0, 1.0, 0.0, 0.5, 0.0, 0.5, 0.75]
         );
     }
 
+    #[test]
+    fn sparse_neuron_ids_should_be_packed_after_sensors_and_outputs() {
+        let mut organism = Organism::new(Genome::default());
+        organism.genome.add_gene(Gene::new(0, 1, 1f64, true));
+        organism.genome.add_gene(Gene::new(0, 40, 1f64, true));
+        organism.genome.add_gene(Gene::new(40, 1, 1f64, true));
+        let indexes = organism.neuron_indexes(2);
+        assert_eq!(indexes.len(), 3);
+        assert_eq!(indexes[&1], 1);
+        assert_eq!(indexes[&40], 2);
+        let mut output = vec![0f64];
+        organism.activate(vec![1f64], &mut output);
+        assert!(output[0] > 0.5f64, "{:?} is not bigger than 0.5", output[0]);
+    }
+
+    #[test]
+    fn bias_should_be_read_from_nodes() {
+        let mut organism = Organism::new(Genome::default());
+        organism.genome.add_gene(Gene::new(0, 1, 1f64, true));
+        let mut node = *organism.genome.get_node(1).unwrap();
+        node.set_bias(0.5f64);
+        organism.genome.add_node(node);
+        let indexes = organism.neuron_indexes(2);
+        assert_eq!(organism.get_bias(&indexes), vec![0f64, 0.5f64]);
+    }
+
+    #[test]
+    fn outputs_should_be_read_from_output_neurons() {
+        let mut organism = Organism::new(Genome::new_unconnected(1, 2));
+        organism.genome.add_gene(Gene::new(0, 2, 10f64, true));
+        let mut output = vec![0f64, 0f64];
+        // extra sensors don't shift the outputs
+        organism.activate(vec![1f64, 1f64, 1f64], &mut output);
+        assert!(output[0].abs() < f64::EPSILON, "{:?}", output);
+        assert!(output[1] > 0.5f64, "{:?}", output);
+    }
+
+    #[test]
+    fn feed_forward_should_return_converged_state_in_one_pass() {
+        let mut organism = Organism::new(Genome::new_unconnected(1, 1));
+        organism.genome.add_gene(Gene::new(0, 2, 2f64, true));
+        organism.genome.add_gene(Gene::new(2, 1, 3f64, true));
+        organism.feed_forward = true;
+        let mut output = vec![0f64];
+        organism.activate(vec![1f64], &mut output);
+        let expected = 3f64 * Ctrnn::sigmoid(2f64 * Ctrnn::sigmoid(1f64));
+        assert!((output[0] - expected).abs() < 1e-12, "{:?}", output);
+
+        organism.feed_forward = false;
+        organism.step_time = 1.0;
+        organism.activate(vec![1f64], &mut output);
+        assert!((output[0] - expected).abs() < 1e-3, "{:?}", output);
+    }
+
+    #[test]
+    fn neuron_states_should_follow_the_last_activation() {
+        let mut organism = Organism::new(Genome::new_unconnected(1, 1));
+        organism.genome.add_gene(Gene::new(0, 2, 2f64, true));
+        organism.genome.add_gene(Gene::new(2, 1, 3f64, true));
+        assert!(organism.neuron_states().is_empty());
+        organism.feed_forward = true;
+        let mut output = vec![0f64];
+        organism.activate(vec![1f64], &mut output);
+        let states = organism.neuron_states();
+        assert_eq!(states.len(), 3);
+        assert!((states[&0] - 1f64).abs() < f64::EPSILON);
+        assert!((states[&2] - 2f64 * Ctrnn::sigmoid(1f64)).abs() < 1e-12);
+        assert!((states[&1] - output[0]).abs() < f64::EPSILON);
+        organism.reset_state();
+        assert!(organism.neuron_states().is_empty());
+    }
+
+    #[test]
+    fn neurons_with_bigger_tau_should_respond_slower() {
+        let mut organism = Organism::new(Genome::new_unconnected(1, 2));
+        organism.genome.add_gene(Gene::new(0, 1, 1f64, true));
+        organism.genome.add_gene(Gene::new(0, 2, 1f64, true));
+        let mut node = *organism.genome.get_node(2).unwrap();
+        node.set_tau(1f64);
+        organism.genome.add_node(node);
+        let mut output = vec![0f64, 0f64];
+        organism.activate(vec![1f64], &mut output);
+        assert!(output[1] < output[0], "{:?}", output);
+    }
+
+    #[test]
+    fn simplified_genome_should_activate_like_the_original() {
+        let mut genome = Genome::new_unconnected(2, 1);
+        genome.add_gene(Gene::new(0, 5, 2f64, true));
+        genome.add_gene(Gene::new(5, 2, 1.5f64, true));
+        genome.add_gene(Gene::new(1, 5, -1f64, true));
+        genome.add_gene(Gene::new(1, 8, 1f64, true));
+        genome.add_gene(Gene::new(8, 8, 1f64, true));
+        genome.add_gene(Gene::new(9, 2, -0.5f64, true));
+        genome.add_gene(Gene::new(0, 2, 3f64, false));
+        for (neuron_id, bias) in &[(2, 0.3f64), (5, -0.2f64), (9, 1f64)] {
+            let mut node = *genome.get_node(*neuron_id).unwrap();
+            node.set_bias(*bias);
+            genome.add_node(node);
+        }
+        let simplified = genome.simplify();
+        assert_eq!(simplified.len(), 5);
+
+        let mut organism = Organism::new(genome);
+        let mut simplified = Organism::new(simplified);
+        for sensors in &[[0f64, 1f64], [1f64, 0f64], [1f64, 1f64]] {
+            let mut output = vec![0f64];
+            let mut simplified_output = vec![0f64];
+            organism.activate(sensors.to_vec(), &mut output);
+            simplified.activate(sensors.to_vec(), &mut simplified_output);
+            assert!((output[0] - simplified_output[0]).abs() < 1e-12);
+        }
+    }
+
     #[test]
     fn should_not_raise_exception_if_less_neurons_than_required() {
         let mut organism = Organism::new(Genome::default());
-        organism.genome.add_gene(Gene::new(0, 1, 1f64, true, false));
+        organism.genome.add_gene(Gene::new(0, 1, 1f64, true));
         let sensors = vec![0f64, 0f64, 0f64];
         let mut output = vec![0f64, 0f64, 0f64];
         organism.activate(sensors, &mut output);
     }
+
+    #[test]
+    fn mate_should_keep_the_excess_genes_of_the_fittest_parent() {
+        let mut fittest = Organism::new(Genome::new_initialized(2, 1));
+        fittest
+            .genome
+            .add_node(NodeGene::new(3, NeuronRole::Hidden));
+        for (in_neuron_id, out_neuron_id, innovation) in &[(0, 3, 10), (3, 2, 11)] {
+            let mut gene = Gene::new(*in_neuron_id, *out_neuron_id, 1f64, true);
+            gene.set_innovation(*innovation);
+            fittest.genome.add_gene(gene);
+        }
+        fittest.fitness = 2f64;
+        let mut other = Organism::new(Genome::new_initialized(2, 1));
+        other.fitness = 1f64;
+        for child in &[fittest.mate(&other), other.mate(&fittest)] {
+            assert_eq!(child.genome.get_genes().len(), 4);
+            assert!(child.genome.get_node(3).is_some());
+        }
+    }
+
+    #[test]
+    fn mate_should_use_the_configured_crossover_operator() {
+        let organism1 = Organism::new(Genome::new_initialized(2, 1));
+        let mut organism2 = organism1.clone();
+        for gene in organism1.genome.get_genes() {
+            let weight = gene.weight() + 2f64;
+            organism2
+                .genome
+                .get_gene_mut(gene.in_neuron_id(), gene.out_neuron_id())
+                .unwrap()
+                .set_weight(weight);
+        }
+        let config = MutationConfig::new()
+            .crossover_operators(vec![(CrossoverOperator::Average, 1f64)])
+            .build();
+        let child = organism1.mate_with_config(&organism2, &config);
+        for gene in organism1.genome.get_genes() {
+            let child_gene = child
+                .genome
+                .get_genes()
+                .iter()
+                .find(|child_gene| child_gene.is_same_innovation(gene))
+                .unwrap();
+            assert!((child_gene.weight() - (gene.weight() + 1f64)).abs() < 1e-12);
+        }
+    }
 }
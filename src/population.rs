@@ -1,23 +1,40 @@
 use crate::environment::Environment;
 use crate::genome::Genome;
+use crate::genome_error::GenomeError;
+use crate::innovation::InnovationTracker;
+use crate::mutation::{Mutation, MutationRegistry};
 use crate::organism::Organism;
+use crate::pruning::{PhasedPruning, PruningPhase};
+#[cfg(feature = "serde")]
+use crate::rng::{EvolutionRng, RngState};
 use conv::prelude::*;
 use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+#[cfg(feature = "serde")]
+use std::fs::{self, File};
+#[cfg(feature = "serde")]
+use std::io::{self, BufReader, BufWriter, Write};
+#[cfg(feature = "serde")]
+use std::path::{Path, PathBuf};
 #[cfg(feature = "telemetry")]
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[cfg(feature = "telemetry")]
 use rusty_dashed;
 
-#[cfg(feature = "telemetry")]
+#[cfg(any(feature = "telemetry", feature = "serde"))]
 use serde_json;
 
 use crate::mutation_config::MutationConfig;
 use crate::specie::Specie;
 use crate::species_evaluator::SpeciesEvaluator;
 
-/// All species in the network
+/// All species in the network.
+/// Mutations added with `register_mutation` are not serialized and must be
+/// registered again on a deserialized population.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Population {
     /// container of species
     pub species: Vec<Specie>,
@@ -27,22 +44,86 @@ pub struct Population {
     pub champion: Option<Organism>,
     /// Mutation configuration
     pub mutation_config: MutationConfig,
+    innovation_tracker: InnovationTracker,
+    pruning: Option<PhasedPruning>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    mutation_registry: MutationRegistry,
+    generation: usize,
+    #[cfg(feature = "serde")]
+    auto_checkpoint: Option<(usize, PathBuf)>,
+    #[cfg(feature = "serde")]
+    #[serde(skip)]
+    checkpoint_error: Option<io::Error>,
+}
+
+#[cfg(feature = "serde")]
+const CHECKPOINT_VERSION: u32 = 1;
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct CheckpointRef<'a> {
+    version: u32,
+    rng_state: RngState,
+    population: &'a Population,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct Checkpoint {
+    version: u32,
+    rng_state: RngState,
+    population: Population,
 }
 
 const MAX_EPOCHS_WITHOUT_IMPROVEMENTS: usize = 50;
-const STAGNATION_THRESHOLD: usize = 15; // Remove species after 15 generations without improvement
-const SPECIES_ELITISM: usize = 2; // Protect top 2 species from stagnation removal
+
+/// Reasons `Population::from_seed_genomes` can't grow a population
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeedError {
+    /// No seed genome was given
+    NoSeed,
+    /// A seed breaks the recurrence policy of the configuration
+    RecurrencePolicy {
+        /// Position of the seed
+        seed: usize,
+        /// Loop the policy forbids
+        error: GenomeError,
+    },
+}
+
+impl fmt::Display for SeedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeedError::NoSeed => write!(f, "At least one seed genome is required"),
+            SeedError::RecurrencePolicy { seed, error } => write!(f, "Seed {}: {}", seed, error),
+        }
+    }
+}
+
+impl Error for SeedError {}
 
 impl Population {
-    /// Create a new population of size X.
-    pub fn create_population(population_size: usize) -> Population {
-        let mut population = Population {
+    fn empty(mutation_config: MutationConfig) -> Population {
+        Population {
             species: vec![],
             champion_fitness: 0f64,
             champion: None,
             epochs_without_improvements: 0usize,
-            mutation_config: MutationConfig::default(),
-        };
+            mutation_config,
+            innovation_tracker: InnovationTracker::new(),
+            pruning: None,
+            mutation_registry: MutationRegistry::new(),
+            generation: 0,
+            #[cfg(feature = "serde")]
+            auto_checkpoint: None,
+            #[cfg(feature = "serde")]
+            checkpoint_error: None,
+        }
+    }
+
+    /// Create a new population of size X.
+    pub fn create_population(population_size: usize) -> Population {
+        let mut population = Population::empty(MutationConfig::default());
 
         population.create_organisms(population_size);
         population
@@ -54,13 +135,7 @@ impl Population {
         input_neurons: usize,
         output_neurons: usize,
     ) -> Population {
-        let mut population = Population {
-            species: vec![],
-            champion_fitness: 0f64,
-            champion: None,
-            epochs_without_improvements: 0usize,
-            mutation_config: MutationConfig::default(),
-        };
+        let mut population = Population::empty(MutationConfig::default());
 
         population.create_organisms_initialized(population_size, input_neurons, output_neurons);
         population
@@ -73,13 +148,7 @@ impl Population {
         output_neurons: usize,
         config: MutationConfig,
     ) -> Population {
-        let mut population = Population {
-            species: vec![],
-            champion_fitness: 0f64,
-            champion: None,
-            epochs_without_improvements: 0usize,
-            mutation_config: config,
-        };
+        let mut population = Population::empty(config);
 
         population.create_organisms_initialized(population_size, input_neurons, output_neurons);
         population
@@ -93,18 +162,70 @@ impl Population {
         output_neurons: usize,
         config: MutationConfig,
     ) -> Population {
-        let mut population = Population {
-            species: vec![],
-            champion_fitness: 0f64,
-            champion: None,
-            epochs_without_improvements: 0usize,
-            mutation_config: config,
-        };
+        let mut population = Population::empty(config);
 
         population.create_organisms_unconnected(population_size, input_neurons, output_neurons);
         population
     }
 
+    /// Create a population grown from a seed genome, see `from_seed_genomes`
+    pub fn from_seed_genome(
+        genome: Genome,
+        population_size: usize,
+        config: MutationConfig,
+    ) -> Result<Population, SeedError> {
+        Population::from_seed_genomes(vec![genome], population_size, config)
+    }
+
+    /// Create a population out of seed genomes, like champions of an earlier run.
+    /// Each seed is kept once as is and the rest of the population are mutated
+    /// copies of the seeds taken in turn, speciated as usual. Seeds are numbered
+    /// with the population's innovation tracker so the same connection has the
+    /// same innovation number in every seed. Fails without seeds or if a seed
+    /// breaks the recurrence policy of the config.
+    pub fn from_seed_genomes(
+        genomes: Vec<Genome>,
+        population_size: usize,
+        config: MutationConfig,
+    ) -> Result<Population, SeedError> {
+        if genomes.is_empty() {
+            return Err(SeedError::NoSeed);
+        }
+        let policy = config.effective_recurrence_policy();
+        for (seed, genome) in genomes.iter().enumerate() {
+            genome
+                .validate_recurrence_policy(policy)
+                .map_err(|error| SeedError::RecurrencePolicy { seed, error })?;
+        }
+        let mut population = Population::empty(config);
+        let mut seeds = genomes;
+        for seed in &mut seeds {
+            population.innovation_tracker.number_genome(seed);
+        }
+
+        let mut organisms = vec![];
+        for (position, seed) in seeds.iter().cycle().take(population_size).enumerate() {
+            let mut genome = seed.clone();
+            if position >= seeds.len() {
+                genome.mutate_with_tracker(
+                    &population.mutation_config,
+                    &mut population.innovation_tracker,
+                );
+                genome.enforce_recurrence_policy(policy);
+            }
+            let mut org = Organism::new(genome);
+            org.step_time = population.mutation_config.step_time;
+            org.feed_forward = population.mutation_config.feed_forward;
+            organisms.push(org);
+        }
+
+        let mut specie = Specie::new(seeds[0].clone());
+        specie.organisms = organisms;
+        population.species.push(specie);
+        population.speciate();
+        Ok(population)
+    }
+
     /// Find total of all organisms in the population
     pub fn size(&self) -> usize {
         self.species
@@ -122,7 +243,7 @@ impl Population {
 
         let stagnation = self.epochs_without_improvements;
         if stagnation <= STAGNATION_START {
-            return self.mutation_config;
+            return self.mutation_config.clone();
         }
 
         let progress = ((stagnation - STAGNATION_START) as f64)
@@ -139,21 +260,105 @@ impl Population {
             add_neuron_rate: (self.mutation_config.add_neuron_rate * multiplier).min(0.20),
             toggle_expression_rate: (self.mutation_config.toggle_expression_rate * multiplier)
                 .min(0.25),
-            toggle_bias_rate: (self.mutation_config.toggle_bias_rate * multiplier).min(0.10),
-            weight_mutation_rate: self.mutation_config.weight_mutation_rate,
-            weight_perturbation_rate: self.mutation_config.weight_perturbation_rate,
-            compatibility_threshold: self.mutation_config.compatibility_threshold,
+            bias_mutation_rate: (self.mutation_config.bias_mutation_rate * multiplier).min(0.50),
             mutation_probability: adaptive_mut_prob,
-            weight_init_range: self.mutation_config.weight_init_range,
-            weight_mutate_power: self.mutation_config.weight_mutate_power,
-            tau: self.mutation_config.tau,
-            step_time: self.mutation_config.step_time,
+            ..self.mutation_config.clone()
         }
     }
 
     /// Create offspring by mutation and mating. May create new species.
     pub fn evolve(&mut self) {
         self.generate_offspring();
+        self.generation += 1;
+        #[cfg(feature = "serde")]
+        self.save_auto_checkpoint();
+    }
+
+    /// Number of calls to `evolve` since the population was created
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Save the population, its configuration, generation number and the state of
+    /// the `EvolutionRng` of the current thread as JSON, so `load_checkpoint`
+    /// resumes the run where it stopped. The checkpoint is written to a temporary
+    /// file renamed over `path`, so a crash while saving keeps the previous one.
+    #[cfg(feature = "serde")]
+    pub fn save_checkpoint<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut temporary_path = path.as_os_str().to_owned();
+        temporary_path.push(".tmp");
+        let temporary_path = PathBuf::from(temporary_path);
+
+        let mut writer = BufWriter::new(File::create(&temporary_path)?);
+        serde_json::to_writer(
+            &mut writer,
+            &CheckpointRef {
+                version: CHECKPOINT_VERSION,
+                rng_state: EvolutionRng::state(),
+                population: self,
+            },
+        )?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        fs::rename(&temporary_path, path)
+    }
+
+    /// Load a population saved with `save_checkpoint` and restore the state of the
+    /// `EvolutionRng` of the current thread. Mutations added with
+    /// `register_mutation` are not saved and must be registered again. Genomes
+    /// breaking the recurrence policy of the configuration are rejected with
+    /// `io::ErrorKind::InvalidData`.
+    #[cfg(feature = "serde")]
+    pub fn load_checkpoint<P: AsRef<Path>>(path: P) -> io::Result<Population> {
+        let checkpoint: Checkpoint = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        if checkpoint.version > CHECKPOINT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported checkpoint version {}", checkpoint.version),
+            ));
+        }
+        let population = checkpoint.population;
+        let policy = population.mutation_config.effective_recurrence_policy();
+        let organisms = population
+            .species
+            .iter()
+            .flat_map(|specie| specie.organisms.iter())
+            .chain(population.champion.iter());
+        for organism in organisms {
+            if let Err(error) = organism.genome.validate_recurrence_policy(policy) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    error.to_string(),
+                ));
+            }
+        }
+        EvolutionRng::set_state(checkpoint.rng_state);
+        Ok(population)
+    }
+
+    /// Save a checkpoint to `path` every `generations` calls to `evolve`, or stop
+    /// saving checkpoints with `None`. Failures don't stop the evolution, see
+    /// `take_checkpoint_error`.
+    #[cfg(feature = "serde")]
+    pub fn set_auto_checkpoint(&mut self, checkpoint: Option<(usize, PathBuf)>) {
+        self.auto_checkpoint = checkpoint;
+    }
+
+    /// Error of the last automatic checkpoint that could not be saved, cleared by
+    /// this call and by the next checkpoint saved
+    #[cfg(feature = "serde")]
+    pub fn take_checkpoint_error(&mut self) -> Option<io::Error> {
+        self.checkpoint_error.take()
+    }
+
+    #[cfg(feature = "serde")]
+    fn save_auto_checkpoint(&mut self) {
+        if let Some((generations, ref path)) = self.auto_checkpoint {
+            if generations > 0 && self.generation.is_multiple_of(generations) {
+                self.checkpoint_error = self.save_checkpoint(path).err();
+            }
+        }
     }
 
     /// Evaluate all organisms in the population using the given environment.
@@ -167,7 +372,10 @@ impl Population {
         }
 
         // Remove stagnant species (but protect top 2 by fitness)
-        self.remove_stagnant_species(STAGNATION_THRESHOLD, SPECIES_ELITISM);
+        self.remove_stagnant_species(
+            self.mutation_config.max_stagnation,
+            self.mutation_config.species_elitism,
+        );
 
         #[cfg(feature = "telemetry")]
         telemetry!("fitness1", 1.0, format!("{}", self.champion_fitness));
@@ -228,8 +436,55 @@ impl Population {
         self.epochs_without_improvements
     }
 
+    /// Innovation numbers and neuron ids handed out to this population
+    pub fn innovation_tracker(&self) -> &InnovationTracker {
+        &self.innovation_tracker
+    }
+
+    /// Apply a custom mutation operator to every child with probability `rate`,
+    /// after the built-in mutations of the mutation config
+    pub fn register_mutation<M: Mutation + 'static>(&mut self, operator: M, rate: f64) {
+        self.mutation_registry.register(operator, rate);
+    }
+
+    /// Custom mutation operators and their rates
+    pub fn mutation_registry(&mut self) -> &mut MutationRegistry {
+        &mut self.mutation_registry
+    }
+
+    /// Alternate complexification and simplification phases, or go back to
+    /// plain complexification with `None`
+    pub fn set_phased_pruning(&mut self, pruning: Option<PhasedPruning>) {
+        self.pruning = pruning;
+    }
+
+    /// Current phase of the phased pruning schedule, if any
+    pub fn pruning_phase(&self) -> Option<PruningPhase> {
+        self.pruning.as_ref().map(|pruning| pruning.phase())
+    }
+
+    /// Mean number of connections plus hidden neurons of the organisms
+    pub fn mean_complexity(&self) -> f64 {
+        let size = self.size();
+        if size == 0 {
+            return 0f64;
+        }
+        let total = self
+            .species
+            .iter()
+            .flat_map(|specie| specie.organisms.iter())
+            .map(|organism| organism.genome.complexity())
+            .sum::<usize>();
+        total.value_as::<f64>().unwrap() / size.value_as::<f64>().unwrap()
+    }
+
     fn generate_offspring(&mut self) {
         self.speciate();
+        self.innovation_tracker.new_generation();
+        let mean_complexity = self.mean_complexity();
+        if let Some(pruning) = self.pruning.as_mut() {
+            pruning.update(mean_complexity);
+        }
 
         let total_average_fitness = self.species.iter_mut().fold(0f64, |total, specie| {
             total + specie.calculate_average_fitness()
@@ -237,16 +492,21 @@ impl Population {
 
         let num_of_organisms = self.size();
         let organisms = self.get_organisms();
-        let config = self.adaptive_config();
+        let config = match self.pruning {
+            Some(ref pruning) => pruning.apply(&self.adaptive_config()),
+            None => self.adaptive_config(),
+        };
 
         if self.epochs_without_improvements > MAX_EPOCHS_WITHOUT_IMPROVEMENTS {
             let mut best_species = self.get_best_species();
             let num_of_selected = best_species.len();
             for specie in &mut best_species {
-                specie.generate_offspring_with_config(
+                specie.generate_offspring_with_registry(
                     num_of_organisms.checked_div(num_of_selected).unwrap(),
                     &organisms,
                     &config,
+                    &mut self.innovation_tracker,
+                    &self.mutation_registry,
                 );
             }
             self.epochs_without_improvements = 0;
@@ -264,7 +524,13 @@ impl Population {
                 (specie_fitness * organisms_by_average_fitness).round() as usize
             };
             if offspring_size > 0 {
-                specie.generate_offspring_with_config(offspring_size, &organisms, &config);
+                specie.generate_offspring_with_registry(
+                    offspring_size,
+                    &organisms,
+                    &config,
+                    &mut self.innovation_tracker,
+                    &self.mutation_registry,
+                );
             } else {
                 specie.remove_organisms();
             }
@@ -319,12 +585,12 @@ impl Population {
             next_specie_id += 1;
         }
 
-        let threshold = self.mutation_config.compatibility_threshold;
+        let config = &self.mutation_config;
         for organism in organisms {
             match self
                 .species
                 .iter_mut()
-                .find(|specie| specie.match_genome_with_threshold(organism, threshold))
+                .find(|specie| specie.match_genome_with_config(organism, config))
             {
                 Some(specie) => {
                     specie.add(organism.clone());
@@ -351,12 +617,16 @@ impl Population {
         let mut organisms = vec![];
 
         while organisms.len() < population_size {
-            let mut org = Organism::new(Genome::new_initialized(input_neurons, output_neurons));
-            org.tau = self.mutation_config.tau;
+            let mut genome = Genome::new_initialized(input_neurons, output_neurons);
+            genome.init_nodes(&self.mutation_config);
+            let mut org = Organism::new(genome);
             org.step_time = self.mutation_config.step_time;
+            org.feed_forward = self.mutation_config.feed_forward;
             organisms.push(org);
         }
 
+        self.innovation_tracker =
+            InnovationTracker::from_genomes(organisms.iter().map(|organism| &organism.genome));
         let mut specie = Specie::new(organisms.first().unwrap().genome.clone());
         specie.organisms = organisms;
         self.species.push(specie);
@@ -372,12 +642,16 @@ impl Population {
         let mut organisms = vec![];
 
         while organisms.len() < population_size {
-            let mut org = Organism::new(Genome::new_unconnected(input_neurons, output_neurons));
-            org.tau = self.mutation_config.tau;
+            let mut genome = Genome::new_unconnected(input_neurons, output_neurons);
+            genome.init_nodes(&self.mutation_config);
+            let mut org = Organism::new(genome);
             org.step_time = self.mutation_config.step_time;
+            org.feed_forward = self.mutation_config.feed_forward;
             organisms.push(org);
         }
 
+        self.innovation_tracker =
+            InnovationTracker::from_genomes(organisms.iter().map(|organism| &organism.genome));
         let mut specie = Specie::new(organisms.first().unwrap().genome.clone());
         specie.organisms = organisms;
         self.species.push(specie);
@@ -397,15 +671,16 @@ mod tests {
     use crate::genome::Genome;
     use crate::organism::Organism;
     use crate::specie::Specie;
+    use std::collections::HashMap;
 
     #[test]
     fn population_should_be_able_to_speciate_genomes() {
         let mut genome1 = Genome::default();
-        genome1.add_gene(Gene::new(0, 0, 1f64, true, false));
-        genome1.add_gene(Gene::new(0, 1, 1f64, true, false));
+        genome1.add_gene(Gene::new(0, 0, 1f64, true));
+        genome1.add_gene(Gene::new(0, 1, 1f64, true));
         let mut genome2 = Genome::default();
-        genome2.add_gene(Gene::new(1, 1, 1f64, true, false));
-        genome2.add_gene(Gene::new(1, 0, 1f64, true, false));
+        genome2.add_gene(Gene::new(1, 1, 1f64, true));
+        genome2.add_gene(Gene::new(1, 0, 1f64, true));
 
         let mut population = Population::create_population(2);
         population.mutation_config.compatibility_threshold = 1.0;
@@ -425,4 +700,203 @@ mod tests {
         }
         assert!(population.size() == 150);
     }
+
+    #[test]
+    fn seeded_population_should_keep_the_seed_and_speciate() {
+        let mut seed = Genome::new_initialized(2, 1);
+        seed.get_gene_mut(0, 2).unwrap().set_weight(0.75);
+        let population = Population::from_seed_genome(seed, 20, MutationConfig::default()).unwrap();
+        assert_eq!(population.size(), 20);
+        assert!(population.get_organisms().iter().any(|organism| {
+            (organism.genome.get_genes()[0].weight() - 0.75).abs() < f64::EPSILON
+        }));
+        assert!(population.innovation_tracker().last_innovation() >= 2);
+    }
+
+    #[test]
+    fn seeds_should_share_innovation_numbers() {
+        let mut seed1 = Genome::default();
+        let mut gene = Gene::new(0, 1, 1f64, true);
+        gene.set_innovation(7);
+        seed1.add_gene(gene);
+        let mut seed2 = Genome::default();
+        let mut gene = Gene::new(0, 1, 1f64, true);
+        gene.set_innovation(3);
+        seed2.add_gene(gene);
+        let mut gene = Gene::new(1, 0, 1f64, true);
+        gene.set_innovation(7);
+        seed2.add_gene(gene);
+        seed2.add_gene(Gene::new(1, 1, 1f64, true));
+
+        let population =
+            Population::from_seed_genomes(vec![seed1, seed2], 2, MutationConfig::default())
+                .unwrap();
+        let mut innovations = HashMap::new();
+        for organism in population.get_organisms() {
+            for gene in organism.genome.get_genes() {
+                let connection = (gene.in_neuron_id(), gene.out_neuron_id());
+                assert_eq!(
+                    *innovations.entry(connection).or_insert(gene.innovation()),
+                    gene.innovation()
+                );
+            }
+        }
+        assert_eq!(innovations[&(0, 1)], 7);
+        assert_eq!(innovations.len(), 3);
+        let mut numbers: Vec<usize> = innovations.values().cloned().collect();
+        numbers.sort();
+        numbers.dedup();
+        assert_eq!(numbers.len(), 3);
+    }
+
+    #[test]
+    fn invalid_seeds_should_be_rejected() {
+        assert_eq!(
+            Population::from_seed_genomes(vec![], 10, MutationConfig::default()).unwrap_err(),
+            SeedError::NoSeed
+        );
+        let mut seed = Genome::new_initialized(1, 1);
+        seed.add_gene(Gene::new(1, 1, 1f64, true));
+        let config = MutationConfig::new().feed_forward(true).build();
+        assert_eq!(
+            Population::from_seed_genomes(vec![Genome::new_initialized(1, 1), seed], 10, config)
+                .unwrap_err(),
+            SeedError::RecurrencePolicy {
+                seed: 1,
+                error: GenomeError::SelfLoop { neuron_id: 1 },
+            }
+        );
+    }
+
+    #[test]
+    fn registered_mutations_should_be_applied_to_offspring() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        struct CountMutations(Arc<AtomicUsize>);
+
+        impl Mutation for CountMutations {
+            fn name(&self) -> &str {
+                "count"
+            }
+
+            fn mutate(&self, _: &mut Genome, _: &MutationConfig, _: &mut InnovationTracker) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let mut population = Population::create_population(10);
+        population.register_mutation(CountMutations(count.clone()), 1f64);
+        population.evolve();
+        assert!(count.load(Ordering::SeqCst) > 0);
+
+        population.mutation_registry().set_rate("count", 0f64);
+        let applied = count.load(Ordering::SeqCst);
+        population.evolve();
+        assert_eq!(count.load(Ordering::SeqCst), applied);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn population_should_round_trip_through_serde() {
+        let mut population = Population::create_population_initialized_with_config(
+            10,
+            2,
+            1,
+            MutationConfig::new().add_neuron_rate(0.5).build(),
+        );
+        population.evolve();
+        let json = serde_json::to_string(&population).unwrap();
+        let loaded: Population = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.size(), population.size());
+        assert_eq!(loaded.species.len(), population.species.len());
+        assert_eq!(
+            loaded.innovation_tracker().last_innovation(),
+            population.innovation_tracker().last_innovation()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn resumed_population_should_evolve_like_the_original() {
+        struct Fitness;
+        impl Environment for Fitness {
+            fn test(&self, organism: &mut Organism) -> f64 {
+                organism.genome.total_weights().abs()
+            }
+        }
+        let snapshot = |population: &Population| {
+            population
+                .get_organisms()
+                .iter()
+                .map(|organism| format!("{:?}", organism.genome))
+                .collect::<Vec<String>>()
+        };
+        let path = std::env::temp_dir().join(format!("rustneat-checkpoint-{}", std::process::id()));
+
+        EvolutionRng::seed(7);
+        let mut population = Population::create_population_initialized_with_config(
+            20,
+            2,
+            1,
+            MutationConfig::new().add_neuron_rate(0.2).build(),
+        );
+        population.set_auto_checkpoint(Some((2, path.clone())));
+        for _ in 0..2 {
+            population.evaluate_in(&Fitness);
+            population.evolve();
+        }
+        population.set_auto_checkpoint(None);
+        for _ in 0..3 {
+            population.evaluate_in(&Fitness);
+            population.evolve();
+        }
+
+        let mut resumed = Population::load_checkpoint(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(resumed.generation(), 2);
+        for _ in 0..3 {
+            resumed.evaluate_in(&Fitness);
+            resumed.evolve();
+        }
+        assert_eq!(resumed.generation(), population.generation());
+        assert_eq!(snapshot(&resumed), snapshot(&population));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn checkpoints_breaking_the_recurrence_policy_should_be_rejected() {
+        use crate::mutation_config::RecurrencePolicy;
+
+        let mut population = Population::create_population_initialized_with_config(
+            2,
+            1,
+            1,
+            MutationConfig::default(),
+        );
+        population.species[0].organisms[0]
+            .genome
+            .add_gene(Gene::new(1, 1, 0.5, true));
+        population.mutation_config.recurrence_policy = RecurrencePolicy::NoSelfLoops;
+        let path =
+            std::env::temp_dir().join(format!("rustneat-checkpoint-policy-{}", std::process::id()));
+        population.save_checkpoint(&path).unwrap();
+        let error = Population::load_checkpoint(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn failed_auto_checkpoints_should_be_reported() {
+        let mut population = Population::create_population(4);
+        let path = std::env::temp_dir()
+            .join(format!("rustneat-missing-{}", std::process::id()))
+            .join("checkpoint");
+        population.set_auto_checkpoint(Some((1, path)));
+        population.evolve();
+        assert!(population.take_checkpoint_error().is_some());
+        assert!(population.take_checkpoint_error().is_none());
+    }
 }
@@ -0,0 +1,130 @@
+use crate::mutation_config::MutationConfig;
+
+/// Phase of a phased pruning schedule
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PruningPhase {
+    /// Structure grows with the usual mutation rates
+    Complexifying,
+    /// No structure is added and connections and neurons are deleted
+    Simplifying,
+}
+
+/// Phased pruning (Green, 2004): the population alternates between growing and
+/// shrinking its genomes. Simplification starts when the mean complexity
+/// (connections plus hidden neurons) grows `complexity_threshold` above the floor
+/// left by the last simplification, and ends when the mean complexity hasn't
+/// dropped for `stall_generations` generations.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhasedPruning {
+    /// Growth of the mean complexity over the floor that starts a simplification
+    pub complexity_threshold: f64,
+    /// Generations without a drop in mean complexity that end a simplification
+    pub stall_generations: usize,
+    /// Delete connection rate while simplifying
+    pub delete_connection_rate: f64,
+    /// Delete neuron rate while simplifying
+    pub delete_neuron_rate: f64,
+    phase: PruningPhase,
+    complexity_floor: Option<f64>,
+    lowest_complexity: f64,
+    generations_without_drop: usize,
+}
+
+impl PhasedPruning {
+    /// Create a schedule that simplifies once the mean complexity grows
+    /// `complexity_threshold` over the floor
+    pub fn new(complexity_threshold: f64) -> PhasedPruning {
+        PhasedPruning {
+            complexity_threshold,
+            stall_generations: 10,
+            delete_connection_rate: 0.2,
+            delete_neuron_rate: 0.1,
+            phase: PruningPhase::Complexifying,
+            complexity_floor: None,
+            lowest_complexity: f64::MAX,
+            generations_without_drop: 0,
+        }
+    }
+
+    /// Current phase
+    pub fn phase(&self) -> PruningPhase {
+        self.phase
+    }
+
+    /// Move to the next phase if the mean complexity of the population asks for it
+    pub fn update(&mut self, mean_complexity: f64) -> PruningPhase {
+        let floor = *self.complexity_floor.get_or_insert(mean_complexity);
+        match self.phase {
+            PruningPhase::Complexifying => {
+                if mean_complexity > floor + self.complexity_threshold {
+                    self.phase = PruningPhase::Simplifying;
+                    self.lowest_complexity = mean_complexity;
+                    self.generations_without_drop = 0;
+                }
+            }
+            PruningPhase::Simplifying => {
+                if mean_complexity < self.lowest_complexity {
+                    self.lowest_complexity = mean_complexity;
+                    self.generations_without_drop = 0;
+                } else {
+                    self.generations_without_drop += 1;
+                }
+                if self.generations_without_drop >= self.stall_generations {
+                    self.phase = PruningPhase::Complexifying;
+                    self.complexity_floor = Some(self.lowest_complexity);
+                }
+            }
+        }
+        self.phase
+    }
+
+    /// Mutation config to use during the current phase
+    pub fn apply(&self, config: &MutationConfig) -> MutationConfig {
+        match self.phase {
+            PruningPhase::Complexifying => config.clone(),
+            PruningPhase::Simplifying => MutationConfig {
+                add_connection_rate: 0f64,
+                add_neuron_rate: 0f64,
+                delete_connection_rate: config
+                    .delete_connection_rate
+                    .max(self.delete_connection_rate),
+                delete_neuron_rate: config.delete_neuron_rate.max(self.delete_neuron_rate),
+                ..config.clone()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_alternate_phases_with_mean_complexity() {
+        let mut pruning = PhasedPruning::new(5f64);
+        pruning.stall_generations = 2;
+        assert_eq!(pruning.update(2f64), PruningPhase::Complexifying);
+        assert_eq!(pruning.update(6f64), PruningPhase::Complexifying);
+        assert_eq!(pruning.update(8f64), PruningPhase::Simplifying);
+        assert_eq!(pruning.update(4f64), PruningPhase::Simplifying);
+        assert_eq!(pruning.update(4f64), PruningPhase::Simplifying);
+        assert_eq!(pruning.update(5f64), PruningPhase::Complexifying);
+        // the new floor is the lowest complexity reached while simplifying
+        assert_eq!(pruning.update(9f64), PruningPhase::Complexifying);
+        assert_eq!(pruning.update(9.5f64), PruningPhase::Simplifying);
+    }
+
+    #[test]
+    fn simplifying_should_stop_additions_and_enable_deletions() {
+        let mut pruning = PhasedPruning::new(0f64);
+        pruning.update(0f64);
+        pruning.update(1f64);
+        let config = pruning.apply(&MutationConfig::default());
+        assert!(config.add_connection_rate.abs() < f64::EPSILON);
+        assert!(config.add_neuron_rate.abs() < f64::EPSILON);
+        assert!(config.delete_connection_rate > 0f64);
+        assert!(config.delete_neuron_rate > 0f64);
+    }
+}
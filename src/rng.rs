@@ -0,0 +1,109 @@
+use rand::{Rand, Rng};
+use std::cell::Cell;
+
+thread_local! {
+    static STATE: Cell<RngState> = Cell::new(RngState::from_entropy());
+}
+
+/// Saved state of the random number generator driving evolution
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RngState([u32; 4]);
+
+impl RngState {
+    fn from_entropy() -> RngState {
+        let mut state = RngState(rand::random());
+        state.fix_zero();
+        state
+    }
+
+    fn from_seed(seed: u64) -> RngState {
+        // splitmix64 spreads close seeds over unrelated states
+        let mut seed = seed;
+        let mut next = || {
+            seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+        let (a, b) = (next(), next());
+        let mut state = RngState([a as u32, (a >> 32) as u32, b as u32, (b >> 32) as u32]);
+        state.fix_zero();
+        state
+    }
+
+    // xorshift never leaves the all zero state
+    fn fix_zero(&mut self) {
+        if self.0 == [0; 4] {
+            self.0[3] = 1;
+        }
+    }
+}
+
+/// Random number generator used by mutation, crossover and selection.
+///
+/// Every thread has its own xorshift128 generator, seeded from the operating
+/// system unless `EvolutionRng::seed` is called. Saving its state with
+/// `EvolutionRng::state` and restoring it with `EvolutionRng::set_state` lets a
+/// resumed run draw the same numbers the interrupted run would have drawn.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct EvolutionRng;
+
+impl EvolutionRng {
+    /// Seed the generator of the current thread, for reproducible runs
+    pub fn seed(seed: u64) {
+        STATE.with(|state| state.set(RngState::from_seed(seed)));
+    }
+
+    /// State of the generator of the current thread
+    pub fn state() -> RngState {
+        STATE.with(Cell::get)
+    }
+
+    /// Restore a state returned by `EvolutionRng::state`
+    pub fn set_state(rng_state: RngState) {
+        let mut rng_state = rng_state;
+        rng_state.fix_zero();
+        STATE.with(|state| state.set(rng_state));
+    }
+}
+
+impl Rng for EvolutionRng {
+    fn next_u32(&mut self) -> u32 {
+        STATE.with(|state| {
+            let RngState([x, y, z, w]) = state.get();
+            let t = x ^ (x << 11);
+            let next = w ^ (w >> 19) ^ (t ^ (t >> 8));
+            state.set(RngState([y, z, w, next]));
+            next
+        })
+    }
+}
+
+/// Handle on the generator of the current thread
+pub(crate) fn thread_rng() -> EvolutionRng {
+    EvolutionRng
+}
+
+/// Random value drawn from the generator of the current thread
+pub(crate) fn random<T: Rand>() -> T {
+    EvolutionRng.gen()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restored_state_should_repeat_the_sequence() {
+        EvolutionRng::seed(42);
+        let state = EvolutionRng::state();
+        let first: Vec<f64> = (0..10).map(|_| random()).collect();
+        EvolutionRng::set_state(state);
+        let second: Vec<f64> = (0..10).map(|_| random()).collect();
+        assert_eq!(first, second);
+        EvolutionRng::seed(43);
+        assert_ne!(random::<f64>(), first[0]);
+    }
+}
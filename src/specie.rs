@@ -1,12 +1,16 @@
+use crate::crossover::{Crossover, CrossoverOperator};
 use crate::genome::Genome;
+use crate::innovation::InnovationTracker;
+use crate::mutation::MutationRegistry;
 use crate::mutation_config::MutationConfig;
 use crate::organism::Organism;
+use crate::rng;
 use conv::prelude::*;
-use rand;
 use rand::Rng;
 
 /// A species (several organisms) and associated fitnesses
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Specie {
     representative: Genome,
     average_fitness: f64,
@@ -52,6 +56,13 @@ impl Specie {
             .is_same_specie_with_threshold(&organism.genome, threshold)
     }
 
+    /// Check if another organism is of the same species using the compatibility
+    /// settings of `config`.
+    pub fn match_genome_with_config(&self, organism: &Organism, config: &MutationConfig) -> bool {
+        self.representative
+            .is_same_specie_with_config(&organism.genome, config)
+    }
+
     /// Get the most performant organism
     pub fn calculate_champion_fitness(&self) -> f64 {
         self.organisms.iter().fold(0f64, |max, organism| {
@@ -128,6 +139,48 @@ impl Specie {
         num_of_organisms: usize,
         population_organisms: &[Organism],
         base_config: &MutationConfig,
+    ) {
+        let mut tracker = InnovationTracker::from_genomes(
+            population_organisms
+                .iter()
+                .chain(self.organisms.iter())
+                .map(|organism| &organism.genome),
+        );
+        self.generate_offspring_with_tracker(
+            num_of_organisms,
+            population_organisms,
+            base_config,
+            &mut tracker,
+        );
+    }
+
+    /// Generate offspring numbering new structure with the population's
+    /// innovation tracker
+    pub fn generate_offspring_with_tracker(
+        &mut self,
+        num_of_organisms: usize,
+        population_organisms: &[Organism],
+        base_config: &MutationConfig,
+        tracker: &mut InnovationTracker,
+    ) {
+        self.generate_offspring_with_registry(
+            num_of_organisms,
+            population_organisms,
+            base_config,
+            tracker,
+            &MutationRegistry::new(),
+        );
+    }
+
+    /// Generate offspring numbering new structure with the population's
+    /// innovation tracker and mutating children with the operators of `registry`
+    pub fn generate_offspring_with_registry(
+        &mut self,
+        num_of_organisms: usize,
+        population_organisms: &[Organism],
+        base_config: &MutationConfig,
+        tracker: &mut InnovationTracker,
+        registry: &MutationRegistry,
     ) {
         self.age += 1;
 
@@ -145,7 +198,7 @@ impl Specie {
 
         let mut offspring: Vec<Organism> = {
             // Fitness-proportionate selection (roulette wheel) using adjusted_fitness
-            let mut rng = rand::thread_rng();
+            let mut rng = rng::thread_rng();
 
             // Calculate total adjusted fitness for roulette wheel
             let total_adjusted_fitness: f64 = self
@@ -181,6 +234,8 @@ impl Specie {
                         &self.organisms[*organism_pos],
                         population_organisms,
                         base_config,
+                        tracker,
+                        registry,
                     )
                 })
                 .collect::<Vec<Organism>>()
@@ -206,7 +261,7 @@ impl Specie {
 
     /// Choice a new representative of the specie at random
     pub fn choose_new_representative(&mut self) {
-        self.representative = rand::thread_rng()
+        self.representative = rng::thread_rng()
             .choose(&self.organisms)
             .unwrap()
             .genome
@@ -247,14 +302,16 @@ impl Specie {
         organism: &Organism,
         population_organisms: &[Organism],
         config: &MutationConfig,
+        tracker: &mut InnovationTracker,
+        registry: &MutationRegistry,
     ) -> Organism {
-        if rand::random::<f64>() < config.mutation_probability || population_organisms.len() < 2 {
+        if rng::random::<f64>() < config.mutation_probability || population_organisms.len() < 2 {
             // 25%: mutation only (asexual reproduction)
-            organism.mutate_with_config(config)
+            organism.mutate_with_registry(config, tracker, registry)
         } else {
             // 75%: crossover then mutate
-            let child = self.create_child_by_mate(organism, population_organisms);
-            child.mutate_with_config(config)
+            let child = self.create_child_by_mate(organism, population_organisms, config);
+            child.mutate_with_registry(config, tracker, registry)
         }
     }
 
@@ -262,17 +319,26 @@ impl Specie {
         &self,
         organism: &Organism,
         population_organisms: &[Organism],
+        config: &MutationConfig,
     ) -> Organism {
-        let mut rng = rand::thread_rng();
-        if rand::random::<f64>() > INTERSPECIE_MATE_PROBABILITY {
-            let selected_mate =
-                rand::seq::sample_iter(&mut rng, 0..self.organisms.len(), 1).unwrap()[0];
-            organism.mate(&self.organisms[selected_mate])
-        } else {
-            let selected_mate =
-                rand::seq::sample_iter(&mut rng, 0..population_organisms.len(), 1).unwrap()[0];
-            organism.mate(&population_organisms[selected_mate])
-        }
+        let operator = CrossoverOperator::choose(&config.crossover_operators)
+            .unwrap_or(CrossoverOperator::Uniform);
+        let mut rng = rng::thread_rng();
+        let mates: Vec<&Organism> = (1..operator.parents())
+            .map(|_| {
+                if rng::random::<f64>() > INTERSPECIE_MATE_PROBABILITY {
+                    let selected_mate =
+                        rand::seq::sample_iter(&mut rng, 0..self.organisms.len(), 1).unwrap()[0];
+                    &self.organisms[selected_mate]
+                } else {
+                    let selected_mate =
+                        rand::seq::sample_iter(&mut rng, 0..population_organisms.len(), 1).unwrap()
+                            [0];
+                    &population_organisms[selected_mate]
+                }
+            })
+            .collect();
+        organism.mate_with(&mates, &operator, config)
     }
 }
 
@@ -281,7 +347,6 @@ mod tests {
     use super::*;
     use crate::genome::Genome;
     use crate::organism::Organism;
-    use std::f64::EPSILON;
 
     #[test]
     fn specie_should_return_correct_average_fitness() {
@@ -299,7 +364,7 @@ mod tests {
         specie.add(organism2);
         specie.add(organism3);
 
-        assert!((specie.calculate_average_fitness() - 15f64).abs() < EPSILON);
+        assert!((specie.calculate_average_fitness() - 15f64).abs() < f64::EPSILON);
     }
 
     #[test]
@@ -322,8 +387,8 @@ mod tests {
         specie.adjust_fitness();
 
         // Each fitness should be divided by species size (3)
-        assert!((specie.organisms[0].adjusted_fitness - 10.0).abs() < EPSILON);
-        assert!((specie.organisms[1].adjusted_fitness - 20.0).abs() < EPSILON);
-        assert!((specie.organisms[2].adjusted_fitness - 30.0).abs() < EPSILON);
+        assert!((specie.organisms[0].adjusted_fitness - 10.0).abs() < f64::EPSILON);
+        assert!((specie.organisms[1].adjusted_fitness - 20.0).abs() < f64::EPSILON);
+        assert!((specie.organisms[2].adjusted_fitness - 30.0).abs() < f64::EPSILON);
     }
 }
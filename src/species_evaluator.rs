@@ -20,7 +20,7 @@ impl<'a> SpeciesEvaluator<'a> {
     }
 
     /// Evaluate all organisms and return the champion
-    pub fn evaluate(&self, species: &mut Vec<Specie>) -> Organism {
+    pub fn evaluate(&self, species: &mut [Specie]) -> Organism {
         if self.threads <= 1 {
             self.evaluate_single(species)
         } else {
@@ -28,7 +28,7 @@ impl<'a> SpeciesEvaluator<'a> {
         }
     }
 
-    fn evaluate_single(&self, species: &mut Vec<Specie>) -> Organism {
+    fn evaluate_single(&self, species: &mut [Specie]) -> Organism {
         let mut champion = Organism::new(Genome::default());
 
         for specie in species.iter_mut() {
@@ -45,7 +45,7 @@ impl<'a> SpeciesEvaluator<'a> {
         champion
     }
 
-    fn evaluate_parallel(&self, species: &mut Vec<Specie>) -> Organism {
+    fn evaluate_parallel(&self, species: &mut [Specie]) -> Organism {
         let original_sizes: Vec<usize> = species.iter().map(|s| s.organisms.len()).collect();
 
         let mut all_organisms: Vec<Organism> = species
@@ -57,7 +57,7 @@ impl<'a> SpeciesEvaluator<'a> {
             return Organism::new(Genome::default());
         }
 
-        let chunk_size = (all_organisms.len() + self.threads - 1) / self.threads;
+        let chunk_size = all_organisms.len().div_ceil(self.threads);
         let (tx, rx) = mpsc::channel();
 
         crossbeam::scope(|scope| {
@@ -80,7 +80,7 @@ impl<'a> SpeciesEvaluator<'a> {
         })
         .unwrap();
 
-        let num_chunks = (all_organisms.len() + chunk_size - 1) / chunk_size;
+        let num_chunks = all_organisms.len().div_ceil(chunk_size);
         let mut champion = Organism::new(Genome::default());
         for _ in 0..num_chunks {
             let local_champion = rx.recv().unwrap();
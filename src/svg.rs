@@ -0,0 +1,360 @@
+use crate::dot::{NEGATIVE_COLOR, POSITIVE_COLOR};
+use crate::genome::Genome;
+use crate::node_gene::{NeuronRole, NodeGene};
+use crate::organism::Organism;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const NEURON_RADIUS: f64 = 14.0;
+const LAYER_SPACING: f64 = 120.0;
+const NEURON_SPACING: f64 = 56.0;
+const MARGIN: f64 = 48.0;
+const MIN_STROKE_WIDTH: f64 = 0.5;
+const MAX_STROKE_WIDTH: f64 = 4.0;
+
+impl Genome {
+    /// SVG drawing of the network, laid out in layers: input neurons on the left,
+    /// output neurons on the right and hidden neurons in between, each one layer
+    /// after the deepest neuron feeding it. Edges are styled like `to_dot`.
+    pub fn to_svg(&self) -> String {
+        render(self, None)
+    }
+
+    /// Write `to_svg` to a file
+    pub fn save_svg<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_svg())
+    }
+}
+
+impl Organism {
+    /// SVG drawing of the genome with the state of each neuron after the last
+    /// `activate` call written above it
+    pub fn to_svg(&self) -> String {
+        render(&self.genome, Some(&self.neuron_states()))
+    }
+
+    /// Write `to_svg` to a file
+    pub fn save_svg<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_svg())
+    }
+}
+
+/// Neuron ids of each layer. Cycles are broken by ignoring the genes leading back
+/// to a neuron whose depth is being computed.
+fn layers(genome: &Genome) -> Vec<Vec<usize>> {
+    let mut incoming: HashMap<usize, Vec<usize>> = HashMap::new();
+    for gene in genome.get_genes() {
+        if gene.in_neuron_id() != gene.out_neuron_id() {
+            incoming
+                .entry(gene.out_neuron_id())
+                .or_default()
+                .push(gene.in_neuron_id());
+        }
+    }
+    let roles = genome.has_io_roles();
+    let mut depths = HashMap::new();
+    for node in genome.get_nodes() {
+        depth(node, genome, &incoming, &mut depths, &mut HashSet::new());
+    }
+
+    let mut layers: Vec<Vec<usize>> = vec![];
+    let mut outputs = vec![];
+    for node in genome.get_nodes() {
+        let layer = match node.role() {
+            NeuronRole::Output if roles => {
+                outputs.push(node.id());
+                continue;
+            }
+            NeuronRole::Hidden if roles => depths[&node.id()].max(1),
+            _ => depths[&node.id()],
+        };
+        if layers.len() <= layer {
+            layers.resize(layer + 1, vec![]);
+        }
+        layers[layer].push(node.id());
+    }
+    if !outputs.is_empty() {
+        layers.push(outputs);
+    }
+    layers.retain(|layer| !layer.is_empty());
+    layers
+}
+
+fn depth(
+    node: &NodeGene,
+    genome: &Genome,
+    incoming: &HashMap<usize, Vec<usize>>,
+    depths: &mut HashMap<usize, usize>,
+    visiting: &mut HashSet<usize>,
+) -> usize {
+    if let Some(depth) = depths.get(&node.id()) {
+        return *depth;
+    }
+    if node.role() == NeuronRole::Input {
+        depths.insert(node.id(), 0);
+        return 0;
+    }
+    visiting.insert(node.id());
+    let mut node_depth = 0;
+    for in_neuron_id in incoming.get(&node.id()).into_iter().flatten() {
+        if visiting.contains(in_neuron_id) {
+            continue;
+        }
+        if let Some(in_node) = genome.get_node(*in_neuron_id) {
+            node_depth = node_depth.max(depth(in_node, genome, incoming, depths, visiting) + 1);
+        }
+    }
+    visiting.remove(&node.id());
+    depths.insert(node.id(), node_depth);
+    node_depth
+}
+
+fn render(genome: &Genome, states: Option<&HashMap<usize, f64>>) -> String {
+    let layers = layers(genome);
+    let tallest_layer = layers.iter().map(Vec::len).max().unwrap_or(1);
+    let width = 2f64 * MARGIN + (layers.len().max(1) - 1) as f64 * LAYER_SPACING;
+    let height = 2f64 * MARGIN + (tallest_layer.max(1) - 1) as f64 * NEURON_SPACING;
+    let mut positions = HashMap::new();
+    for (layer_index, layer) in layers.iter().enumerate() {
+        let top = (height - (layer.len() - 1) as f64 * NEURON_SPACING) / 2f64;
+        for (position, neuron_id) in layer.iter().enumerate() {
+            positions.insert(
+                *neuron_id,
+                (
+                    MARGIN + layer_index as f64 * LAYER_SPACING,
+                    top + position as f64 * NEURON_SPACING,
+                ),
+            );
+        }
+    }
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0:.0}\" height=\"{1:.0}\" \
+         viewBox=\"0 0 {0:.0} {1:.0}\" font-family=\"sans-serif\" font-size=\"10\">",
+        width, height
+    )
+    .unwrap();
+    writeln!(svg, "  <defs>").unwrap();
+    for (name, color) in &[
+        ("positive", format!("#{}", POSITIVE_COLOR)),
+        ("negative", format!("#{}", NEGATIVE_COLOR)),
+        ("disabled", "#999999".to_string()),
+    ] {
+        writeln!(
+            svg,
+            "    <marker id=\"arrow-{}\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" \
+             markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\">\
+             <path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"{}\"/></marker>",
+            name, color
+        )
+        .unwrap();
+    }
+    writeln!(svg, "  </defs>").unwrap();
+
+    let max_weight = genome
+        .get_genes()
+        .iter()
+        .map(|gene| gene.weight().abs())
+        .fold(0f64, f64::max);
+    for gene in genome.get_genes() {
+        let (from, to) = match (
+            positions.get(&gene.in_neuron_id()),
+            positions.get(&gene.out_neuron_id()),
+        ) {
+            (Some(from), Some(to)) => (*from, *to),
+            _ => continue,
+        };
+        let magnitude = if max_weight > 0f64 {
+            gene.weight().abs() / max_weight
+        } else {
+            0f64
+        };
+        let (kind, color, dash) = if !gene.enabled() {
+            (
+                "disabled",
+                "#999999".to_string(),
+                " stroke-dasharray=\"4 3\"",
+            )
+        } else if gene.weight() < 0f64 {
+            ("negative", format!("#{}", NEGATIVE_COLOR), "")
+        } else {
+            ("positive", format!("#{}", POSITIVE_COLOR), "")
+        };
+        writeln!(
+            svg,
+            "  <path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{:.2}\" \
+             stroke-opacity=\"{:.2}\"{} marker-end=\"url(#arrow-{})\"/>",
+            edge_path(from, to),
+            color,
+            MIN_STROKE_WIDTH + (MAX_STROKE_WIDTH - MIN_STROKE_WIDTH) * magnitude,
+            0.25 + 0.75 * magnitude,
+            dash,
+            kind
+        )
+        .unwrap();
+    }
+
+    for node in genome.get_nodes() {
+        let (x, y) = positions[&node.id()];
+        let fill = match node.role() {
+            NeuronRole::Input => "#deebf7",
+            NeuronRole::Output => "#fee0d2",
+            NeuronRole::Hidden => "#ffffff",
+        };
+        if node.role() == NeuronRole::Input {
+            writeln!(
+                svg,
+                "  <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{2:.1}\" height=\"{2:.1}\" \
+                 fill=\"{3}\" stroke=\"#333333\"/>",
+                x - NEURON_RADIUS,
+                y - NEURON_RADIUS,
+                2f64 * NEURON_RADIUS,
+                fill
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                svg,
+                "  <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"{}\" stroke=\"#333333\"/>",
+                x, y, NEURON_RADIUS, fill
+            )
+            .unwrap();
+        }
+        writeln!(
+            svg,
+            "  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            x,
+            y + 3.5,
+            node.id()
+        )
+        .unwrap();
+        if node.bias() != 0f64 {
+            writeln!(
+                svg,
+                "  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" fill=\"#555555\">b={:+.2}</text>",
+                x,
+                y + NEURON_RADIUS + 11.0,
+                node.bias()
+            )
+            .unwrap();
+        }
+        if let Some(state) = states.and_then(|states| states.get(&node.id())) {
+            writeln!(
+                svg,
+                "  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" fill=\"#6a3d9a\">{:.3}</text>",
+                x,
+                y - NEURON_RADIUS - 5.0,
+                state
+            )
+            .unwrap();
+        }
+    }
+    writeln!(svg, "</svg>").unwrap();
+    svg
+}
+
+/// Path from the border of one neuron to the border of another. Self connections
+/// loop on the right of the neuron and connections that don't go right bend, so they don't
+/// cross the neurons in between.
+fn edge_path(from: (f64, f64), to: (f64, f64)) -> String {
+    let (x1, y1) = from;
+    let (x2, y2) = to;
+    if (x1 - x2).abs() < f64::EPSILON && (y1 - y2).abs() < f64::EPSILON {
+        return format!(
+            "M {:.1} {:.1} C {:.1} {:.1} {:.1} {:.1} {:.1} {:.1}",
+            x1 + 0.7 * NEURON_RADIUS,
+            y1 - 0.7 * NEURON_RADIUS,
+            x1 + 3.0 * NEURON_RADIUS,
+            y1 - 2.5 * NEURON_RADIUS,
+            x1 + 3.0 * NEURON_RADIUS,
+            y1 + 2.5 * NEURON_RADIUS,
+            x1 + 0.7 * NEURON_RADIUS,
+            y1 + 0.7 * NEURON_RADIUS
+        );
+    }
+    let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+    let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+    if x2 > x1 {
+        return format!(
+            "M {:.1} {:.1} L {:.1} {:.1}",
+            x1 + dx * NEURON_RADIUS,
+            y1 + dy * NEURON_RADIUS,
+            x2 - dx * NEURON_RADIUS,
+            y2 - dy * NEURON_RADIUS
+        );
+    }
+    let bend = NEURON_SPACING * 0.6;
+    let (cx, cy) = ((x1 + x2) / 2f64 - dy * bend, (y1 + y2) / 2f64 + dx * bend);
+    let toward = |x: f64, y: f64| {
+        let length = ((cx - x).powi(2) + (cy - y).powi(2)).sqrt();
+        (
+            x + (cx - x) / length * NEURON_RADIUS,
+            y + (cy - y) / length * NEURON_RADIUS,
+        )
+    };
+    let (sx, sy) = toward(x1, y1);
+    let (ex, ey) = toward(x2, y2);
+    format!(
+        "M {:.1} {:.1} Q {:.1} {:.1} {:.1} {:.1}",
+        sx, sy, cx, cy, ex, ey
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gene::Gene;
+
+    fn genome() -> Genome {
+        let mut genome = Genome::new_initialized(2, 1);
+        genome.add_node(NodeGene::new(3, NeuronRole::Hidden));
+        genome.add_node(NodeGene::new(4, NeuronRole::Hidden));
+        genome.add_gene(Gene::new(0, 3, 1f64, true));
+        genome.add_gene(Gene::new(3, 4, 1f64, true));
+        genome.add_gene(Gene::new(4, 3, -1f64, false));
+        genome.add_gene(Gene::new(4, 2, 1f64, true));
+        genome.add_gene(Gene::new(2, 2, 0.5f64, true));
+        genome
+    }
+
+    #[test]
+    fn layers_should_follow_depth_and_break_cycles() {
+        assert_eq!(
+            layers(&genome()),
+            vec![vec![0, 1], vec![3], vec![4], vec![2]]
+        );
+
+        let mut genome = Genome::default();
+        genome.add_gene(Gene::new(0, 1, 1f64, true));
+        genome.add_gene(Gene::new(1, 2, 1f64, true));
+        genome.add_gene(Gene::new(2, 0, 1f64, true));
+        assert_eq!(layers(&genome).concat().len(), 3);
+    }
+
+    #[test]
+    fn svg_should_draw_every_neuron_and_gene() {
+        let svg = genome().to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect").count(), 2);
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert_eq!(svg.matches("\n  <path d=\"M").count(), 7);
+        assert_eq!(svg.matches("stroke-dasharray").count(), 1);
+        assert!(svg.contains("url(#arrow-disabled)"));
+    }
+
+    #[test]
+    fn organism_svg_should_show_neuron_states() {
+        let mut organism = Organism::new(genome());
+        assert!(!organism.to_svg().contains("#6a3d9a\">"));
+        organism.activate(vec![1f64, 0f64], &mut [0f64]);
+        let svg = organism.to_svg();
+        assert_eq!(svg.matches("fill=\"#6a3d9a\"").count(), 5);
+        assert!(svg.contains(">1.000</text>"));
+    }
+}